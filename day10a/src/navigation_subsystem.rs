@@ -121,7 +121,7 @@ mod tests_day10a {
         navigation_subsystem.read_lines_from_input_file("sample_input.txt");
 
         assert_eq!(navigation_subsystem.input_values.len(), 10);
-        assert_eq!(navigation_subsystem.input_values.get(0).unwrap().len(), 24);
+        assert_eq!(navigation_subsystem.input_values.first().unwrap().len(), 24);
     }
 
     #[test]
@@ -137,7 +137,7 @@ mod tests_day10a {
 
         for chunk in simple_chunks {
             println!("Checking chunk {}", chunk);
-            assert_eq!(navigation_subsystem.is_chunk_valid(&chunk).0, true);
+            assert!(navigation_subsystem.is_chunk_valid(&chunk).0);
         }
     }
 
@@ -153,7 +153,7 @@ mod tests_day10a {
         simple_chunks.push("<{([([[(<>()){}]>(<<{{".to_string());
 
         for chunk in simple_chunks {
-            assert_eq!(navigation_subsystem.is_chunk_valid(&chunk).0, false);
+            assert!(!navigation_subsystem.is_chunk_valid(&chunk).0);
         }
     }
 
@@ -161,12 +161,13 @@ mod tests_day10a {
     fn test_calculate_score_for_invalid_brackets() {
         let navigation_subsystem = NavigationSubsystem::new();
 
-        let mut simple_chunks: Vec<String> = Vec::new();
-        simple_chunks.push("{([(<{}[<>[]}>{[]{[(<()>".to_string());
-        simple_chunks.push("[[<[([]))<([[{}[[()]]]".to_string());
-        simple_chunks.push("[{[{({}]{}}([{[{{{}}([]".to_string());
-        simple_chunks.push("[<(<(<(<{}))><([]([]()".to_string());
-        simple_chunks.push("<{([([[(<>()){}]>(<<{{".to_string());
+        let simple_chunks: Vec<String> = vec![
+            "{([(<{}[<>[]}>{[]{[(<()>".to_string(),
+            "[[<[([]))<([[{}[[()]]]".to_string(),
+            "[{[{({}]{}}([{[{{{}}([]".to_string(),
+            "[<(<(<(<{}))><([]([]()".to_string(),
+            "<{([([[(<>()){}]>(<<{{".to_string(),
+        ];
 
         assert_eq!(
             navigation_subsystem.calculate_score_for_invalid_brackets_in_chunks(&simple_chunks),
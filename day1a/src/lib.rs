@@ -1,26 +1,22 @@
+pub use cmilbert_aoc_day1b::depth::Depth;
 use std::{
     fs::File,
-    io::{prelude::*, BufReader, Error, ErrorKind},
+    io::{prelude::*, BufReader, Error},
 };
 
-pub fn read_lines_from_input_file(input_file_name: &str) -> Result<Vec<i32>, Error> {
+pub fn read_lines_from_input_file<T: Depth>(input_file_name: &str) -> Result<Vec<T>, Error> {
     let io = File::open(input_file_name)?;
     let br = BufReader::new(io);
     br.lines()
-        .map(|line| line.and_then(|v| v.parse().map_err(|e| Error::new(ErrorKind::InvalidData, e))))
+        .map(|line| line.and_then(|v| T::parse_depth(&v)))
         .collect()
 }
 
-pub fn count_of_increasing_measurements(measurements: Vec<i32>) -> i32 {
-    let mut number_of_increasing_readings = 0;
-    let mut previous_value = measurements[0];
-    for line in measurements {
-        if line > previous_value {
-            number_of_increasing_readings += 1;
-        }
-        previous_value = line;
-    }
-    number_of_increasing_readings
+pub fn count_of_increasing_measurements<T: Depth>(measurements: Vec<T>) -> i32 {
+    measurements
+        .windows(2)
+        .filter(|pair| pair[1] > pair[0])
+        .count() as i32
 }
 
 #[cfg(test)]
@@ -32,4 +28,15 @@ mod tests {
         let measurements = vec![199, 200, 208, 210, 200, 207, 240, 269, 260, 263];
         assert_eq!(count_of_increasing_measurements(measurements), 7)
     }
+
+    #[test]
+    fn test_generic_measurements() {
+        let large: Vec<u64> = vec![u64::MAX - 2, u64::MAX - 1, u64::MAX, 0];
+        assert_eq!(count_of_increasing_measurements(large), 2);
+
+        let fractional: Vec<f64> = vec![1.5, 1.25, 1.75];
+        assert_eq!(count_of_increasing_measurements(fractional), 1);
+
+        assert_eq!(count_of_increasing_measurements(Vec::<i64>::new()), 0);
+    }
 }
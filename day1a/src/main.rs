@@ -2,7 +2,7 @@ use ::cmilbert_aoc_day1a::{count_of_increasing_measurements, read_lines_from_inp
 use std::io::Error;
 
 fn main() -> Result<(), Error> {
-    let measurements = read_lines_from_input_file("input.txt")?;
+    let measurements = read_lines_from_input_file::<i64>("input.txt")?;
     let increasing_measurements_count = count_of_increasing_measurements(measurements);
     println!("Increasing readings: {}", increasing_measurements_count);
     Ok(())
@@ -0,0 +1,321 @@
+use crate::depth::Depth;
+use std::{collections::VecDeque, fmt, io::prelude::*, io::Error};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AnomalyKind<T: Depth = i32> {
+    Missing,
+    NonNumeric(String),
+    Spike {
+        value: T,
+        expected: f64,
+        std_dev: f64,
+    },
+    SignFlip {
+        previous: T,
+        current: T,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Anomaly<T: Depth = i32> {
+    pub line_number: usize,
+    pub kind: AnomalyKind<T>,
+}
+
+impl<T: Depth> fmt::Display for Anomaly<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.kind {
+            AnomalyKind::Missing => write!(f, "line {}: missing reading", self.line_number),
+            AnomalyKind::NonNumeric(text) => {
+                write!(
+                    f,
+                    "line {}: non-numeric reading {:?}",
+                    self.line_number, text
+                )
+            }
+            AnomalyKind::Spike {
+                value,
+                expected,
+                std_dev,
+            } => write!(
+                f,
+                "line {}: spike {} (expected {:.2}, std dev of changes {:.2})",
+                self.line_number, value, expected, std_dev
+            ),
+            AnomalyKind::SignFlip { previous, current } => write!(
+                f,
+                "line {}: sign flip from {} to {}",
+                self.line_number, previous, current
+            ),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct AnomalyScan<T: Depth = i32> {
+    pub measurements: Vec<T>,
+    pub anomalies: Vec<Anomaly<T>>,
+}
+
+// A spike is a change from the last normal reading more than `max_std_devs`
+// deviations (at least `min_std_dev`) from the window's mean change.
+pub struct AnomalyDetector {
+    window_size: usize,
+    max_std_devs: f64,
+    min_std_dev: f64,
+}
+
+impl Default for AnomalyDetector {
+    fn default() -> Self {
+        Self::new(50, 6.0)
+    }
+}
+
+impl AnomalyDetector {
+    // The minimum deviation defaults to one depth unit.
+    pub fn new(window_size: usize, max_std_devs: f64) -> Self {
+        AnomalyDetector {
+            window_size,
+            max_std_devs,
+            min_std_dev: 1.0,
+        }
+    }
+
+    pub fn with_min_std_dev(self, min_std_dev: f64) -> Self {
+        AnomalyDetector {
+            min_std_dev,
+            ..self
+        }
+    }
+
+    pub fn scan<T: Depth>(&self, reader: impl BufRead) -> Result<AnomalyScan<T>, Error> {
+        let mut scan: AnomalyScan<T> = AnomalyScan {
+            measurements: Vec::new(),
+            anomalies: Vec::new(),
+        };
+        // The changes between the last `window_size` normal readings.
+        let changes_in_window = self.window_size.saturating_sub(1);
+        let mut changes: VecDeque<f64> = VecDeque::with_capacity(changes_in_window);
+        let mut last_normal: Option<f64> = None;
+        let mut last_spike: Option<f64> = None;
+        let mut spikes_since_normal = 0;
+
+        for (index, line) in reader.lines().enumerate() {
+            let line = line?;
+            let line_number = index + 1;
+            let text = line.trim();
+
+            if text.is_empty() {
+                scan.anomalies.push(Anomaly {
+                    line_number,
+                    kind: AnomalyKind::Missing,
+                });
+                continue;
+            }
+
+            let value = match T::parse_depth(text) {
+                Ok(value) => value,
+                Err(_) => {
+                    scan.anomalies.push(Anomaly {
+                        line_number,
+                        kind: AnomalyKind::NonNumeric(text.to_string()),
+                    });
+                    continue;
+                }
+            };
+
+            if let Some(previous) = scan.measurements.last() {
+                if is_sign_flip(previous.to_f64(), value.to_f64()) {
+                    scan.anomalies.push(Anomaly {
+                        line_number,
+                        kind: AnomalyKind::SignFlip {
+                            previous: previous.clone(),
+                            current: value.clone(),
+                        },
+                    });
+                }
+            }
+
+            if let Some(last) = last_normal.filter(|_| changes_in_window > 0) {
+                let window_is_full = changes.len() == changes_in_window;
+                let (mean, std_dev) = if window_is_full {
+                    mean_and_std_dev(&changes)
+                } else {
+                    (0.0, 0.0)
+                };
+                let deviation = std_dev.max(self.min_std_dev);
+                let is_outlier = |change: f64| {
+                    window_is_full
+                        && deviation > 0.0
+                        && (change - mean).abs() > self.max_std_devs * deviation
+                };
+
+                // The change per reading since the last normal one.
+                let mut change = (value.to_f64() - last) / (spikes_since_normal + 1) as f64;
+                let mut is_spike = is_outlier(change);
+                if let Some(spike) = last_spike.filter(|_| is_spike) {
+                    // In line with the previous spike, so the level has shifted.
+                    if !is_outlier(value.to_f64() - spike) {
+                        change = value.to_f64() - spike;
+                        is_spike = false;
+                    }
+                }
+
+                if is_spike {
+                    scan.anomalies.push(Anomaly {
+                        line_number,
+                        kind: AnomalyKind::Spike {
+                            value: value.clone(),
+                            expected: last + mean * (spikes_since_normal + 1) as f64,
+                            std_dev,
+                        },
+                    });
+                    last_spike = Some(value.to_f64());
+                    spikes_since_normal += 1;
+                } else {
+                    if window_is_full {
+                        changes.pop_front();
+                    }
+                    changes.push_back(change);
+                    last_normal = Some(value.to_f64());
+                    last_spike = None;
+                    spikes_since_normal = 0;
+                }
+            } else {
+                last_normal = Some(value.to_f64());
+            }
+            scan.measurements.push(value);
+        }
+
+        Ok(scan)
+    }
+}
+
+fn is_sign_flip(previous: f64, current: f64) -> bool {
+    (previous < 0.0 && current > 0.0) || (previous > 0.0 && current < 0.0)
+}
+
+fn mean_and_std_dev(window: &VecDeque<f64>) -> (f64, f64) {
+    let count = window.len() as f64;
+    let mean = window.iter().sum::<f64>() / count;
+    let variance = window
+        .iter()
+        .map(|value| (value - mean).powi(2))
+        .sum::<f64>()
+        / count;
+    (mean, variance.sqrt())
+}
+
+#[cfg(test)]
+mod tests_anomaly {
+    use super::*;
+
+    #[test]
+    fn test_clean_input_has_no_anomalies() {
+        let input = "100\n102\n101\n103\n102\n104\n103\n105\n";
+        let scan: AnomalyScan = AnomalyDetector::new(4, 4.0).scan(input.as_bytes()).unwrap();
+        assert_eq!(scan.measurements.len(), 8);
+        assert!(scan.anomalies.is_empty());
+    }
+
+    #[test]
+    fn test_missing_and_non_numeric_lines() {
+        let input = "199\n\n208\nabc\n210\n";
+        let scan: AnomalyScan = AnomalyDetector::default().scan(input.as_bytes()).unwrap();
+        assert_eq!(scan.measurements, vec![199, 208, 210]);
+        assert_eq!(
+            scan.anomalies,
+            vec![
+                Anomaly {
+                    line_number: 2,
+                    kind: AnomalyKind::Missing
+                },
+                Anomaly {
+                    line_number: 4,
+                    kind: AnomalyKind::NonNumeric("abc".to_string())
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_spike() {
+        let input = "100\n101\n99\n100\n5000\n101\n";
+        let scan: AnomalyScan = AnomalyDetector::new(4, 3.0).scan(input.as_bytes()).unwrap();
+        assert_eq!(scan.anomalies.len(), 1);
+        assert_eq!(scan.anomalies[0].line_number, 5);
+        assert!(matches!(
+            scan.anomalies[0].kind,
+            AnomalyKind::Spike { value: 5000, .. }
+        ));
+    }
+
+    #[test]
+    fn test_plateau_then_small_change() {
+        let input = "100\n100\n100\n100\n100\n101\n100\n";
+        let scan: AnomalyScan = AnomalyDetector::new(4, 3.0).scan(input.as_bytes()).unwrap();
+        assert!(scan.anomalies.is_empty());
+
+        // Without a minimum deviation only a perfectly flat window is
+        // skipped; it never flags the change that ends the plateau.
+        let scan: AnomalyScan = AnomalyDetector::new(4, 3.0)
+            .with_min_std_dev(0.0)
+            .scan(input.as_bytes())
+            .unwrap();
+        assert!(scan.anomalies.is_empty());
+
+        let scan: AnomalyScan = AnomalyDetector::new(4, 3.0)
+            .scan("100\n100\n100\n100\n104\n".as_bytes())
+            .unwrap();
+        assert_eq!(scan.anomalies.len(), 1);
+    }
+
+    #[test]
+    fn test_steady_trend_is_not_a_spike() {
+        let input: String = (0..50).map(|i| format!("{}\n", 1000 - i * 7)).collect();
+        let scan: AnomalyScan = AnomalyDetector::new(5, 3.0).scan(input.as_bytes()).unwrap();
+        assert!(scan.anomalies.is_empty());
+
+        let with_spike = input.replace("860\n", "100\n");
+        let scan: AnomalyScan = AnomalyDetector::new(5, 3.0)
+            .scan(with_spike.as_bytes())
+            .unwrap();
+        assert_eq!(scan.anomalies.len(), 1);
+        assert!(matches!(
+            scan.anomalies[0].kind,
+            AnomalyKind::Spike { value: 100, expected, .. } if expected == 860.0
+        ));
+    }
+
+    #[test]
+    fn test_level_shift() {
+        let input = "100\n101\n100\n101\n100\n500\n501\n500\n501\n";
+        let scan: AnomalyScan = AnomalyDetector::new(4, 3.0).scan(input.as_bytes()).unwrap();
+        let lines: Vec<usize> = scan.anomalies.iter().map(|a| a.line_number).collect();
+        assert_eq!(lines, vec![6]);
+    }
+
+    #[test]
+    fn test_sign_flip() {
+        let input = "3\n0\n-2\n4\n";
+        let scan: AnomalyScan = AnomalyDetector::new(0, 3.0).scan(input.as_bytes()).unwrap();
+        assert_eq!(
+            scan.anomalies,
+            vec![Anomaly {
+                line_number: 4,
+                kind: AnomalyKind::SignFlip {
+                    previous: -2,
+                    current: 4
+                }
+            }]
+        );
+    }
+
+    #[test]
+    fn test_fractional_readings() {
+        let input = "1.5\n-0.5\n2\n";
+        let scan: AnomalyScan<f64> = AnomalyDetector::new(0, 3.0).scan(input.as_bytes()).unwrap();
+        assert_eq!(scan.measurements, vec![1.5, -0.5, 2.0]);
+        assert_eq!(scan.anomalies.len(), 2);
+    }
+}
@@ -0,0 +1,248 @@
+use crate::{
+    count_of_increasing_measurements_sliding_window,
+    depth::Depth,
+    sonar_sweep::{window_stats, Trend, WindowStats},
+};
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, HashMap},
+    io::{prelude::*, Error, ErrorKind},
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChannelReading<T: Depth = i32> {
+    pub timestamp: String,
+    pub channel: String,
+    pub depth: T,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChannelSummary<T: Depth = i32> {
+    pub channel: String,
+    pub readings: usize,
+    pub increases: i32,
+    pub window_increases: i32,
+    pub window_stats: Vec<WindowStats<T>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Disagreement<T: Depth = i32> {
+    pub timestamp: String,
+    pub depths: Vec<(String, T)>,
+    pub spread: f64,
+    pub conflicting_trends: bool,
+}
+
+pub struct ChannelLog<T: Depth = i32> {
+    readings: Vec<ChannelReading<T>>,
+}
+
+impl<T: Depth> ChannelLog<T> {
+    pub fn new(readings: Vec<ChannelReading<T>>) -> Self {
+        ChannelLog { readings }
+    }
+
+    pub fn read(reader: impl BufRead) -> Result<Self, Error> {
+        let mut readings = Vec::new();
+        for (index, line) in reader.lines().enumerate() {
+            let line = line?;
+            let text = line.trim();
+            if text.is_empty() || (index == 0 && text.to_lowercase().starts_with("timestamp")) {
+                continue;
+            }
+            readings.push(parse_channel_reading(text, index + 1)?);
+        }
+        Ok(ChannelLog { readings })
+    }
+
+    pub fn readings(&self) -> &[ChannelReading<T>] {
+        &self.readings
+    }
+
+    pub fn channels(&self) -> BTreeMap<&str, Vec<T>> {
+        let mut channels: BTreeMap<&str, Vec<T>> = BTreeMap::new();
+        for reading in &self.readings {
+            channels
+                .entry(reading.channel.as_str())
+                .or_default()
+                .push(reading.depth.clone());
+        }
+        channels
+    }
+
+    pub fn summaries(&self, window_size: usize) -> Result<Vec<ChannelSummary<T>>, Error> {
+        self.channels()
+            .into_iter()
+            .map(|(channel, depths)| {
+                Ok(ChannelSummary {
+                    channel: channel.to_string(),
+                    readings: depths.len(),
+                    window_stats: window_stats(&depths, window_size)?,
+                    increases: count_of_increasing_measurements_sliding_window(depths.clone(), 1)?,
+                    window_increases: count_of_increasing_measurements_sliding_window(
+                        depths,
+                        window_size,
+                    )?,
+                })
+            })
+            .collect()
+    }
+
+    // A timestamp is reported when the channels that sampled it differ by more
+    // than `tolerance`, or when some moved up while others moved down since
+    // their previous readings.
+    pub fn disagreements(&self, tolerance: f64) -> Vec<Disagreement<T>> {
+        let mut timestamps: Vec<&str> = Vec::new();
+        let mut samples: HashMap<&str, Vec<(String, T, Trend)>> = HashMap::new();
+        let mut previous_depths: HashMap<&str, &T> = HashMap::new();
+
+        for reading in &self.readings {
+            let trend = match previous_depths.insert(&reading.channel, &reading.depth) {
+                Some(previous) => match reading.depth.partial_cmp(previous) {
+                    Some(Ordering::Greater) => Trend::Increase,
+                    Some(Ordering::Less) => Trend::Decrease,
+                    _ => Trend::Plateau,
+                },
+                None => Trend::Plateau,
+            };
+            let entry = samples.entry(&reading.timestamp).or_insert_with(|| {
+                timestamps.push(&reading.timestamp);
+                Vec::new()
+            });
+            entry.push((reading.channel.clone(), reading.depth.clone(), trend));
+        }
+
+        timestamps
+            .into_iter()
+            .filter_map(|timestamp| {
+                let sampled = samples.remove(timestamp)?;
+                if sampled.len() < 2 {
+                    return None;
+                }
+
+                let values: Vec<f64> = sampled.iter().map(|(_, depth, _)| depth.to_f64()).collect();
+                let max = values.iter().cloned().fold(f64::MIN, f64::max);
+                let min = values.iter().cloned().fold(f64::MAX, f64::min);
+                let spread = max - min;
+                let conflicting_trends = sampled.iter().any(|s| s.2 == Trend::Increase)
+                    && sampled.iter().any(|s| s.2 == Trend::Decrease);
+
+                if spread <= tolerance && !conflicting_trends {
+                    return None;
+                }
+                Some(Disagreement {
+                    timestamp: timestamp.to_string(),
+                    depths: sampled
+                        .into_iter()
+                        .map(|(channel, depth, _)| (channel, depth))
+                        .collect(),
+                    spread,
+                    conflicting_trends,
+                })
+            })
+            .collect()
+    }
+}
+
+fn parse_channel_reading<T: Depth>(
+    text: &str,
+    line_number: usize,
+) -> Result<ChannelReading<T>, Error> {
+    let fields: Vec<&str> = text.split(',').map(|field| field.trim()).collect();
+    if fields.len() != 3 {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "line {}: expected timestamp,channel,depth but found {} fields",
+                line_number,
+                fields.len()
+            ),
+        ));
+    }
+
+    let depth = T::parse_depth(fields[2]).map_err(|e| {
+        Error::new(
+            ErrorKind::InvalidData,
+            format!("line {}: invalid depth {:?}: {}", line_number, fields[2], e),
+        )
+    })?;
+    Ok(ChannelReading {
+        timestamp: fields[0].to_string(),
+        channel: fields[1].to_string(),
+        depth,
+    })
+}
+
+#[cfg(test)]
+mod tests_channels {
+    use super::*;
+
+    fn get_sample_log() -> &'static str {
+        "timestamp,channel,depth\n\
+         1,port,199\n\
+         1,starboard,198\n\
+         2,port,200\n\
+         2,starboard,201\n\
+         3,port,208\n\
+         3,starboard,207\n\
+         4,port,210\n\
+         4,starboard,209\n\
+         5,port,200\n\
+         5,starboard,230\n\
+         6,port,207\n"
+    }
+
+    #[test]
+    fn test_read_channel_log() {
+        let log: ChannelLog = ChannelLog::read(get_sample_log().as_bytes()).unwrap();
+        assert_eq!(log.readings().len(), 11);
+        assert_eq!(log.readings()[1].channel, "starboard");
+
+        let channels = log.channels();
+        assert_eq!(channels["port"], vec![199, 200, 208, 210, 200, 207]);
+        assert_eq!(channels["starboard"].len(), 5);
+    }
+
+    #[test]
+    fn test_malformed_line() {
+        let error = ChannelLog::<i32>::read("1,port,199\n2,port\n".as_bytes())
+            .err()
+            .unwrap();
+        assert!(error.to_string().starts_with("line 2:"));
+
+        let error = ChannelLog::<i32>::read("1,port,deep\n".as_bytes())
+            .err()
+            .unwrap();
+        assert!(error.to_string().starts_with("line 1:"));
+    }
+
+    #[test]
+    fn test_channel_summaries() {
+        let log: ChannelLog = ChannelLog::read(get_sample_log().as_bytes()).unwrap();
+        let summaries = log.summaries(3).unwrap();
+        assert_eq!(summaries.len(), 2);
+
+        assert_eq!(summaries[0].channel, "port");
+        assert_eq!(summaries[0].readings, 6);
+        assert_eq!(summaries[0].increases, 4);
+        assert_eq!(summaries[0].window_increases, 1);
+        assert_eq!(summaries[0].window_stats[0].sum, 607);
+
+        assert_eq!(summaries[1].channel, "starboard");
+        assert_eq!(summaries[1].increases, 4);
+    }
+
+    #[test]
+    fn test_disagreements() {
+        let log: ChannelLog = ChannelLog::read(get_sample_log().as_bytes()).unwrap();
+        let disagreements = log.disagreements(5.0);
+        assert_eq!(disagreements.len(), 1);
+        assert_eq!(disagreements[0].timestamp, "5");
+        assert_eq!(disagreements[0].spread, 30.0);
+        assert!(disagreements[0].conflicting_trends);
+        assert_eq!(
+            disagreements[0].depths,
+            vec![("port".to_string(), 200), ("starboard".to_string(), 230)]
+        );
+    }
+}
@@ -0,0 +1,163 @@
+use std::{
+    fmt,
+    io::{Error, ErrorKind},
+};
+
+// Window sums accumulate in a wider `Sum` type where one exists, and every
+// accumulation is checked so an overflow surfaces as an error rather than a
+// wrapped total. Big integers sum into themselves and never overflow.
+pub trait Depth: Clone + PartialOrd + fmt::Debug + fmt::Display {
+    type Sum: Clone + PartialOrd + fmt::Debug;
+
+    fn parse_depth(text: &str) -> Result<Self, Error>;
+    fn zero_sum() -> Self::Sum;
+    fn checked_add_to(sum: &Self::Sum, depth: &Self) -> Option<Self::Sum>;
+    fn checked_sub_from(sum: &Self::Sum, depth: &Self) -> Option<Self::Sum>;
+    fn sum_to_f64(sum: &Self::Sum) -> f64;
+    fn to_f64(&self) -> f64;
+}
+
+macro_rules! impl_integer_depth {
+    ($($depth:ty => $sum:ty),*) => {
+        $(
+            impl Depth for $depth {
+                type Sum = $sum;
+
+                fn parse_depth(text: &str) -> Result<Self, Error> {
+                    text.parse().map_err(|e| Error::new(ErrorKind::InvalidData, e))
+                }
+
+                fn zero_sum() -> $sum {
+                    0
+                }
+
+                fn checked_add_to(sum: &$sum, depth: &Self) -> Option<$sum> {
+                    sum.checked_add(*depth as $sum)
+                }
+
+                fn checked_sub_from(sum: &$sum, depth: &Self) -> Option<$sum> {
+                    sum.checked_sub(*depth as $sum)
+                }
+
+                fn sum_to_f64(sum: &$sum) -> f64 {
+                    *sum as f64
+                }
+
+                fn to_f64(&self) -> f64 {
+                    *self as f64
+                }
+            }
+        )*
+    };
+}
+
+impl_integer_depth!(
+    i32 => i64,
+    i64 => i128,
+    i128 => i128,
+    u32 => u64,
+    u64 => u128,
+    u128 => u128
+);
+
+impl Depth for f64 {
+    type Sum = f64;
+
+    fn parse_depth(text: &str) -> Result<Self, Error> {
+        text.parse()
+            .map_err(|e| Error::new(ErrorKind::InvalidData, e))
+    }
+
+    fn zero_sum() -> f64 {
+        0.0
+    }
+
+    fn checked_add_to(sum: &f64, depth: &Self) -> Option<f64> {
+        Some(sum + depth).filter(|total| total.is_finite())
+    }
+
+    fn checked_sub_from(sum: &f64, depth: &Self) -> Option<f64> {
+        Some(sum - depth).filter(|total| total.is_finite())
+    }
+
+    fn sum_to_f64(sum: &f64) -> f64 {
+        *sum
+    }
+
+    fn to_f64(&self) -> f64 {
+        *self
+    }
+}
+
+#[cfg(feature = "bigint")]
+impl Depth for num_bigint::BigInt {
+    type Sum = num_bigint::BigInt;
+
+    fn parse_depth(text: &str) -> Result<Self, Error> {
+        text.parse()
+            .map_err(|e| Error::new(ErrorKind::InvalidData, e))
+    }
+
+    fn zero_sum() -> Self {
+        Self::default()
+    }
+
+    fn checked_add_to(sum: &Self, depth: &Self) -> Option<Self> {
+        Some(sum + depth)
+    }
+
+    fn checked_sub_from(sum: &Self, depth: &Self) -> Option<Self> {
+        Some(sum - depth)
+    }
+
+    fn sum_to_f64(sum: &Self) -> f64 {
+        num_traits::ToPrimitive::to_f64(sum).unwrap_or(f64::NAN)
+    }
+
+    fn to_f64(&self) -> f64 {
+        Self::sum_to_f64(self)
+    }
+}
+
+pub fn window_sum_overflow() -> Error {
+    Error::new(ErrorKind::InvalidData, "window sum overflowed")
+}
+
+#[cfg(test)]
+mod tests_depth {
+    use super::*;
+
+    #[test]
+    fn test_parse_depth() {
+        assert_eq!(i64::parse_depth("-12").unwrap(), -12);
+        assert_eq!(f64::parse_depth("1.5").unwrap(), 1.5);
+        assert!(u64::parse_depth("-12").is_err());
+    }
+
+    #[test]
+    fn test_widened_sum_does_not_overflow() {
+        let sum = i32::checked_add_to(&i32::zero_sum(), &i32::MAX).unwrap();
+        let sum = i32::checked_add_to(&sum, &i32::MAX).unwrap();
+        assert_eq!(sum, 2 * i32::MAX as i64);
+    }
+
+    #[test]
+    fn test_checked_sum_overflow() {
+        assert!(u128::checked_add_to(&u128::MAX, &1).is_none());
+        assert!(f64::checked_add_to(&f64::MAX, &f64::MAX).is_none());
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn test_bigint_depth() {
+        use num_bigint::BigInt;
+
+        let deep = BigInt::parse_depth("340282366920938463463374607431768211456").unwrap();
+        let sum = BigInt::checked_add_to(&BigInt::zero_sum(), &deep).unwrap();
+        let sum = BigInt::checked_add_to(&sum, &deep).unwrap();
+        assert_eq!(sum, &deep * 2);
+        assert_eq!(BigInt::checked_sub_from(&sum, &deep), Some(deep.clone()));
+        assert_eq!(deep.to_f64(), 2f64.powi(128));
+        assert!(BigInt::parse_depth("12.5").is_err());
+    }
+}
@@ -1,37 +1,38 @@
+pub mod anomaly;
+pub mod channels;
+pub mod depth;
+pub mod sliding_window;
+pub mod sonar_sweep;
+
+use anomaly::{AnomalyDetector, AnomalyScan};
+use depth::Depth;
+use sliding_window::count_increasing_window_sums;
 use std::{
     fs::File,
-    io::{prelude::*, BufReader, Error, ErrorKind},
+    io::{prelude::*, BufReader, Error},
 };
 
-pub fn read_lines_from_input_file(input_file_name: &str) -> Result<Vec<i32>, Error> {
+pub fn read_lines_from_input_file<T: Depth>(input_file_name: &str) -> Result<Vec<T>, Error> {
+    let io = File::open(input_file_name)?;
+    read_measurements(BufReader::new(io)).collect()
+}
+
+pub fn scan_input_file<T: Depth>(input_file_name: &str) -> Result<AnomalyScan<T>, Error> {
     let io = File::open(input_file_name)?;
-    let br = BufReader::new(io);
-    br.lines()
-        .map(|line| line.and_then(|v| v.parse().map_err(|e| Error::new(ErrorKind::InvalidData, e))))
-        .collect()
+    AnomalyDetector::default().scan(BufReader::new(io))
 }
 
-pub fn count_of_increasing_measurements_sliding_window(
-    measurements: Vec<i32>,
+pub fn read_measurements<T: Depth>(reader: impl BufRead) -> impl Iterator<Item = Result<T, Error>> {
+    reader
+        .lines()
+        .map(|line| line.and_then(|v| T::parse_depth(&v)))
+}
+
+pub fn count_of_increasing_measurements_sliding_window<T: Depth>(
+    measurements: Vec<T>,
     window_size: usize,
-) -> i32 {
-    let mut number_of_increasing_measurements = 0;
-    let mut current_sum;
-    let mut previous_sum = 0;
-    for i in 0..(measurements.len() - window_size) {
-        current_sum = 0;
-
-        for measurement in measurements.iter().skip(i).take(window_size) {
-            current_sum += measurement;
-        }
-
-        if current_sum > previous_sum {
-            number_of_increasing_measurements += 1;
-        }
-
-        previous_sum = current_sum;
-    }
-    number_of_increasing_measurements
+) -> Result<i32, Error> {
+    Ok(count_increasing_window_sums(measurements, window_size)? as i32)
 }
 
 #[cfg(test)]
@@ -42,8 +43,26 @@ mod tests_day1b {
     fn test_sample_data() {
         let measurements = vec![199, 200, 208, 210, 200, 207, 240, 269, 260, 263];
         assert_eq!(
-            count_of_increasing_measurements_sliding_window(measurements, 3),
+            count_of_increasing_measurements_sliding_window(measurements, 3).unwrap(),
             5
         )
     }
+
+    #[test]
+    fn test_read_measurements() {
+        let input = "199\n200\n208\n";
+        let measurements: Vec<i32> = read_measurements(input.as_bytes())
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(measurements, vec![199, 200, 208]);
+        assert!(read_measurements::<i32>("1\nx\n".as_bytes()).any(|m| m.is_err()));
+    }
+
+    #[test]
+    fn test_read_fractional_measurements() {
+        let measurements: Vec<f64> = read_measurements("1.25\n2.5\n".as_bytes())
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(measurements, vec![1.25, 2.5]);
+    }
 }
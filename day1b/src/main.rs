@@ -1,12 +1,91 @@
 use ::cmilbert_aoc_day1b::{
-    count_of_increasing_measurements_sliding_window, read_lines_from_input_file,
+    channels::ChannelLog, count_of_increasing_measurements_sliding_window, read_measurements,
+    scan_input_file, sliding_window::count_increasing_window_sums, sonar_sweep::SonarSweepReport,
 };
-use std::io::Error;
+use std::{
+    env,
+    fs::File,
+    io::{self, BufReader, Error, ErrorKind},
+};
+
+fn count_from_stdin(window_size: usize) -> Result<usize, Error> {
+    let mut read_error: Option<Error> = None;
+    let measurements = read_measurements::<i64>(io::stdin().lock())
+        .map_while(|measurement| measurement.map_err(|e| read_error = Some(e)).ok());
+    let increasing_measurements_count = count_increasing_window_sums(measurements, window_size);
+    match read_error {
+        Some(e) => Err(e),
+        None => increasing_measurements_count,
+    }
+}
+
+// Channels within this many depth units of each other are taken to agree;
+// smaller differences are sensor noise rather than a disagreement.
+const DEFAULT_DISAGREEMENT_TOLERANCE: f64 = 5.0;
+
+fn report_channels(input_file_name: &str, window_size: usize, tolerance: f64) -> Result<(), Error> {
+    let log: ChannelLog<i64> = ChannelLog::read(BufReader::new(File::open(input_file_name)?))?;
+    for summary in log.summaries(window_size)? {
+        println!(
+            "Channel {}: {} readings, {} increasing, {} increasing windows",
+            summary.channel, summary.readings, summary.increases, summary.window_increases
+        );
+    }
+    for disagreement in log.disagreements(tolerance) {
+        println!(
+            "Disagreement at {}: spread {}{}",
+            disagreement.timestamp,
+            disagreement.spread,
+            if disagreement.conflicting_trends {
+                ", conflicting trends"
+            } else {
+                ""
+            }
+        );
+    }
+    Ok(())
+}
 
 fn main() -> Result<(), Error> {
-    let measurements = read_lines_from_input_file("input.txt")?;
+    let args: Vec<String> = env::args().collect();
+    match args.get(1).map(String::as_str) {
+        Some("-") => {
+            println!("Increasing readings: {}", count_from_stdin(3)?);
+            return Ok(());
+        }
+        Some("--channels") => {
+            // Usage: --channels [file] [tolerance]
+            let input_file_name = args.get(2).map(String::as_str).unwrap_or("channels.csv");
+            let tolerance = match args.get(3) {
+                Some(tolerance) => tolerance.parse().map_err(|_| {
+                    Error::new(
+                        ErrorKind::InvalidInput,
+                        format!("invalid tolerance {:?}", tolerance),
+                    )
+                })?,
+                None => DEFAULT_DISAGREEMENT_TOLERANCE,
+            };
+            return report_channels(input_file_name, 3, tolerance);
+        }
+        _ => {}
+    }
+
+    let scan = scan_input_file::<i64>("input.txt")?;
+    for anomaly in &scan.anomalies {
+        println!("Anomaly at {}", anomaly);
+    }
+
+    let measurements = scan.measurements;
+    let report = SonarSweepReport::new(&measurements, 3)?;
     let increasing_measurements_count =
-        count_of_increasing_measurements_sliding_window(measurements, 3);
+        count_of_increasing_measurements_sliding_window(measurements, 3)?;
     println!("Increasing readings: {}", increasing_measurements_count);
+    if let Some(streak) = report.longest_increasing_streak {
+        println!(
+            "Longest increasing streak: {} readings starting at line {}",
+            streak.length + 1,
+            streak.start + 1
+        );
+    }
     Ok(())
 }
@@ -0,0 +1,157 @@
+use crate::depth::{window_sum_overflow, Depth};
+use std::{collections::VecDeque, io::Error};
+
+pub struct WindowSums<I, T: Depth> {
+    measurements: I,
+    window: VecDeque<T>,
+    window_size: usize,
+    sum: T::Sum,
+    overflowed: bool,
+}
+
+impl<I: Iterator<Item = T>, T: Depth> WindowSums<I, T> {
+    pub fn new(measurements: I, window_size: usize) -> Self {
+        WindowSums {
+            measurements,
+            window: VecDeque::with_capacity(window_size),
+            window_size,
+            sum: T::zero_sum(),
+            overflowed: false,
+        }
+    }
+
+    fn overflow(&mut self) -> Option<Result<T::Sum, Error>> {
+        self.overflowed = true;
+        Some(Err(window_sum_overflow()))
+    }
+}
+
+impl<I: Iterator<Item = T>, T: Depth> Iterator for WindowSums<I, T> {
+    type Item = Result<T::Sum, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.window_size == 0 || self.overflowed {
+            return None;
+        }
+
+        if self.window.len() == self.window_size {
+            let leaving = self.window.pop_front().unwrap();
+            match T::checked_sub_from(&self.sum, &leaving) {
+                Some(sum) => self.sum = sum,
+                None => return self.overflow(),
+            }
+        }
+
+        while self.window.len() < self.window_size {
+            let measurement = self.measurements.next()?;
+            match T::checked_add_to(&self.sum, &measurement) {
+                Some(sum) => self.sum = sum,
+                None => return self.overflow(),
+            }
+            self.window.push_back(measurement);
+        }
+
+        Some(Ok(self.sum.clone()))
+    }
+}
+
+pub trait SlidingWindowExt<T: Depth>: Iterator<Item = T> + Sized {
+    fn window_sums(self, window_size: usize) -> WindowSums<Self, T> {
+        WindowSums::new(self, window_size)
+    }
+}
+
+impl<I: Iterator<Item = T>, T: Depth> SlidingWindowExt<T> for I {}
+
+pub fn count_increasing_window_sums<T: Depth>(
+    measurements: impl IntoIterator<Item = T>,
+    window_size: usize,
+) -> Result<usize, Error> {
+    let mut window_sums = measurements.into_iter().window_sums(window_size);
+    let mut previous_sum = match window_sums.next() {
+        Some(sum) => sum?,
+        None => return Ok(0),
+    };
+
+    let mut number_of_increasing_sums = 0;
+    for current_sum in window_sums {
+        let current_sum = current_sum?;
+        if current_sum > previous_sum {
+            number_of_increasing_sums += 1;
+        }
+        previous_sum = current_sum;
+    }
+    Ok(number_of_increasing_sums)
+}
+
+#[cfg(test)]
+mod tests_sliding_window {
+    use super::*;
+
+    fn get_sample_data() -> Vec<i32> {
+        vec![199, 200, 208, 210, 200, 207, 240, 269, 260, 263]
+    }
+
+    #[test]
+    fn test_window_sums() {
+        let sums: Vec<i64> = get_sample_data()
+            .into_iter()
+            .window_sums(3)
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(sums, vec![607, 618, 618, 617, 647, 716, 769, 792]);
+    }
+
+    #[test]
+    fn test_window_larger_than_input() {
+        assert_eq!(get_sample_data().into_iter().window_sums(11).count(), 0);
+        assert_eq!(
+            count_increasing_window_sums(get_sample_data(), 11).unwrap(),
+            0
+        );
+        assert_eq!(
+            count_increasing_window_sums(Vec::<i32>::new(), 3).unwrap(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_zero_window_size() {
+        assert_eq!(get_sample_data().into_iter().window_sums(0).count(), 0);
+    }
+
+    #[test]
+    fn test_counts_final_window() {
+        assert_eq!(
+            count_increasing_window_sums(vec![1, 1, 1, 2], 3).unwrap(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_unbounded_stream() {
+        let measurements = (0..).take_while(|&measurement: &i32| measurement < 1000);
+        assert_eq!(count_increasing_window_sums(measurements, 3).unwrap(), 997);
+    }
+
+    #[test]
+    fn test_generic_depths() {
+        let large: Vec<i64> = vec![i64::MAX, i64::MAX, i64::MAX, i64::MAX];
+        assert_eq!(count_increasing_window_sums(large, 3).unwrap(), 0);
+
+        let fractional: Vec<f64> = vec![1.5, 2.25, 2.0, 3.5];
+        assert_eq!(count_increasing_window_sums(fractional, 2).unwrap(), 2);
+
+        let unsigned: Vec<u64> = vec![u64::MAX, 0, u64::MAX, 1];
+        assert_eq!(count_increasing_window_sums(unsigned, 2).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_overflowing_window_sum() {
+        let measurements: Vec<u128> = vec![u128::MAX, 1, 2];
+        let mut window_sums = measurements.into_iter().window_sums(2);
+        assert!(window_sums.next().unwrap().is_err());
+        assert!(window_sums.next().is_none());
+        assert!(count_increasing_window_sums(vec![u128::MAX, 1, 2], 2).is_err());
+    }
+}
@@ -0,0 +1,232 @@
+use crate::depth::{window_sum_overflow, Depth};
+use std::{cmp::Ordering, io::Error};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trend {
+    Increase,
+    Decrease,
+    Plateau,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Run {
+    pub trend: Trend,
+    pub start: usize,
+    pub length: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct WindowStats<T: Depth = i32> {
+    pub start: usize,
+    pub sum: T::Sum,
+    pub mean: f64,
+    pub min: T,
+    pub max: T,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SonarSweepReport<T: Depth = i32> {
+    pub window_size: usize,
+    pub window_stats: Vec<WindowStats<T>>,
+    pub derivative: Vec<f64>,
+    pub runs: Vec<Run>,
+    pub longest_increasing_streak: Option<Run>,
+    pub longest_decreasing_streak: Option<Run>,
+}
+
+impl<T: Depth> SonarSweepReport<T> {
+    pub fn new(measurements: &[T], window_size: usize) -> Result<Self, Error> {
+        let runs = runs(measurements);
+        Ok(SonarSweepReport {
+            window_size,
+            window_stats: window_stats(measurements, window_size)?,
+            derivative: derivative(measurements),
+            longest_increasing_streak: longest_run(&runs, Trend::Increase),
+            longest_decreasing_streak: longest_run(&runs, Trend::Decrease),
+            runs,
+        })
+    }
+
+    pub fn run_lengths(&self, trend: Trend) -> Vec<usize> {
+        self.runs
+            .iter()
+            .filter(|run| run.trend == trend)
+            .map(|run| run.length)
+            .collect()
+    }
+}
+
+pub fn window_stats<T: Depth>(
+    measurements: &[T],
+    window_size: usize,
+) -> Result<Vec<WindowStats<T>>, Error> {
+    if window_size == 0 {
+        return Ok(Vec::new());
+    }
+
+    measurements
+        .windows(window_size)
+        .enumerate()
+        .map(|(start, window)| {
+            let sum = window
+                .iter()
+                .try_fold(T::zero_sum(), |sum, measurement| {
+                    T::checked_add_to(&sum, measurement)
+                })
+                .ok_or_else(window_sum_overflow)?;
+            let min = window
+                .iter()
+                .fold(&window[0], |min, m| if m < min { m } else { min });
+            let max = window
+                .iter()
+                .fold(&window[0], |max, m| if m > max { m } else { max });
+            Ok(WindowStats {
+                start,
+                mean: T::sum_to_f64(&sum) / window_size as f64,
+                sum,
+                min: min.clone(),
+                max: max.clone(),
+            })
+        })
+        .collect()
+}
+
+pub fn derivative<T: Depth>(measurements: &[T]) -> Vec<f64> {
+    measurements
+        .windows(2)
+        .map(|pair| pair[1].to_f64() - pair[0].to_f64())
+        .collect()
+}
+
+fn trend_of<T: Depth>(previous: &T, current: &T) -> Trend {
+    match current.partial_cmp(previous) {
+        Some(Ordering::Greater) => Trend::Increase,
+        Some(Ordering::Less) => Trend::Decrease,
+        _ => Trend::Plateau,
+    }
+}
+
+// Runs are indexed by the position of the first change, so a run starting at
+// `start` with `length` changes spans measurements `start..=start + length`.
+pub fn runs<T: Depth>(measurements: &[T]) -> Vec<Run> {
+    let mut runs: Vec<Run> = Vec::new();
+    for (position, pair) in measurements.windows(2).enumerate() {
+        let trend = trend_of(&pair[0], &pair[1]);
+        match runs.last_mut() {
+            Some(run) if run.trend == trend => run.length += 1,
+            _ => runs.push(Run {
+                trend,
+                start: position,
+                length: 1,
+            }),
+        }
+    }
+    runs
+}
+
+fn longest_run(runs: &[Run], trend: Trend) -> Option<Run> {
+    runs.iter()
+        .filter(|run| run.trend == trend)
+        .fold(None, |longest: Option<Run>, run| match longest {
+            Some(longest) if longest.length >= run.length => Some(longest),
+            _ => Some(*run),
+        })
+}
+
+#[cfg(test)]
+mod tests_sonar_sweep {
+    use super::*;
+
+    fn get_sample_data() -> Vec<i32> {
+        vec![199, 200, 208, 210, 200, 207, 240, 269, 260, 263]
+    }
+
+    #[test]
+    fn test_window_stats() {
+        let stats = window_stats(&get_sample_data(), 3).unwrap();
+        assert_eq!(stats.len(), 8);
+        assert_eq!(stats[0].sum, 607);
+        assert_eq!(stats[7].sum, 792);
+        assert_eq!(stats[7].mean, 264.0);
+        assert_eq!(stats[3].min, 200);
+        assert_eq!(stats[3].max, 210);
+    }
+
+    #[test]
+    fn test_window_larger_than_input() {
+        assert!(window_stats(&get_sample_data(), 11).unwrap().is_empty());
+        assert!(window_stats(&get_sample_data(), 0).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_derivative() {
+        assert_eq!(
+            derivative(&get_sample_data()),
+            vec![1.0, 8.0, 2.0, -10.0, 7.0, 33.0, 29.0, -9.0, 3.0]
+        );
+        assert!(derivative(&[5]).is_empty());
+    }
+
+    #[test]
+    fn test_runs() {
+        let runs = runs(&[0, 1, 9, 9, 9, -1, 6]);
+        assert_eq!(
+            runs,
+            vec![
+                Run {
+                    trend: Trend::Increase,
+                    start: 0,
+                    length: 2
+                },
+                Run {
+                    trend: Trend::Plateau,
+                    start: 2,
+                    length: 2
+                },
+                Run {
+                    trend: Trend::Decrease,
+                    start: 4,
+                    length: 1
+                },
+                Run {
+                    trend: Trend::Increase,
+                    start: 5,
+                    length: 1
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sample_report() {
+        let report = SonarSweepReport::new(&get_sample_data(), 3).unwrap();
+        assert_eq!(report.run_lengths(Trend::Increase), vec![3, 3, 1]);
+        assert_eq!(report.run_lengths(Trend::Decrease), vec![1, 1]);
+        assert!(report.run_lengths(Trend::Plateau).is_empty());
+        assert_eq!(
+            report.longest_increasing_streak,
+            Some(Run {
+                trend: Trend::Increase,
+                start: 0,
+                length: 3
+            })
+        );
+        assert_eq!(report.longest_decreasing_streak.unwrap().start, 3);
+    }
+
+    #[test]
+    fn test_fractional_report() {
+        let report = SonarSweepReport::new(&[1.5, 2.5, 2.5, 1.0], 2).unwrap();
+        assert_eq!(report.window_stats[0].sum, 4.0);
+        assert_eq!(report.window_stats[2].min, 1.0);
+        assert_eq!(report.run_lengths(Trend::Plateau), vec![1]);
+    }
+
+    #[test]
+    fn test_large_depths() {
+        let measurements: Vec<u64> = vec![u64::MAX - 1, u64::MAX];
+        let report = SonarSweepReport::new(&measurements, 2).unwrap();
+        assert_eq!(report.window_stats[0].sum, 2 * u64::MAX as u128 - 1);
+        assert_eq!(report.run_lengths(Trend::Increase), vec![1]);
+    }
+}
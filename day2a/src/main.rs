@@ -1,4 +1,4 @@
-use ::cmilbert_aoc_day2a::Submarine;
+use ::cmilbert_aoc_day2a::{Submarine, SubmarineError};
 
 use std::{
     fs::File,
@@ -14,9 +14,10 @@ fn read_lines_from_input_file(filename: impl AsRef<Path>) -> Vec<String> {
         .collect()
 }
 
-fn main() {
+fn main() -> Result<(), SubmarineError> {
     let mut submarine = Submarine::new(0, 0);
     let instructions = read_lines_from_input_file("input.txt");
-    submarine.process_instructions(instructions);
+    submarine.process_instructions(instructions)?;
     println!("Final position: {}", submarine.multiply_positions());
+    Ok(())
 }
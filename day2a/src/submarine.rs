@@ -1,50 +1,51 @@
+use cmilbert_aoc_day2b::DirectModel;
+
+pub use cmilbert_aoc_day2b::{
+    parse_commands, Command, CommandError, MovementError, ParseCommandError, SubmarineError,
+    SubmarineState,
+};
+
+// Part one's submarine: the shared day 2 submarine under the direct model, so
+// `down` increases the depth and `forward` only moves horizontally.
 pub struct Submarine {
-    horizontal_position: u32,
-    vertical_position: i32,
+    inner: cmilbert_aoc_day2b::Submarine,
 }
 
 impl Submarine {
-    pub fn new(horizontal_position: u32, vertical_position: i32) -> Self {
+    pub fn new(horizontal_position: i64, depth: i64) -> Self {
         Submarine {
-            horizontal_position,
-            vertical_position,
+            inner: cmilbert_aoc_day2b::Submarine::from_state(
+                Box::new(DirectModel),
+                SubmarineState {
+                    horizontal_position,
+                    vertical_position: depth,
+                    ..SubmarineState::default()
+                },
+            ),
         }
     }
 
-    pub fn process_instructions(&mut self, instructions: Vec<String>) {
-        for instruction in instructions {
-            self.process_instruction(instruction)
-        }
+    pub fn state(&self) -> SubmarineState {
+        self.inner.state()
     }
 
-    fn process_instruction(&mut self, instruction: String) {
-        let split = instruction.split(' ');
-        let split_vec = split.collect::<Vec<&str>>();
-        let command = split_vec[0].to_lowercase();
-        let count = split_vec[1];
-
-        match command.as_ref() {
-            "forward" => self.forward(count.parse::<u32>().unwrap()),
-            "up" => self.up(count.parse::<i32>().unwrap()),
-            "down" => self.down(count.parse::<i32>().unwrap()),
-            _ => println!("Invalid command"),
-        }
+    pub fn process_instructions(
+        &mut self,
+        instructions: Vec<String>,
+    ) -> Result<(), SubmarineError> {
+        self.inner.process_instructions(instructions)
     }
 
-    fn forward(&mut self, count: u32) {
-        self.horizontal_position += count;
+    pub fn process_instruction(&mut self, instruction: &str) -> Result<(), SubmarineError> {
+        self.inner.process_instruction(instruction)
     }
 
-    fn down(&mut self, count: i32) {
-        self.vertical_position -= count;
+    pub fn apply(&mut self, command: Command) -> Result<(), MovementError> {
+        self.inner.apply(command)
     }
 
-    fn up(&mut self, count: i32) {
-        self.vertical_position += count;
-    }
-
-    pub fn multiply_positions(&self) -> i32 {
-        (self.horizontal_position as i32 * self.vertical_position).abs()
+    pub fn multiply_positions(&self) -> i128 {
+        self.inner.multiply_positions()
     }
 }
 
@@ -54,56 +55,57 @@ mod tests_day2a {
 
     #[test]
     fn test_create_submarine() {
-        let submarine = Submarine::new(0, 0);
-        assert_eq!(submarine.horizontal_position, 0);
-        assert_eq!(submarine.vertical_position, 0);
+        let state = Submarine::new(0, 0).state();
+        assert_eq!(state.horizontal_position, 0);
+        assert_eq!(state.vertical_position, 0);
     }
 
     #[test]
     fn test_move_submarine_forward() {
         let mut submarine = Submarine::new(0, 0);
 
-        submarine.forward(5);
-        assert_eq!(submarine.horizontal_position, 5);
+        submarine.apply(Command::Forward(5)).unwrap();
+        assert_eq!(submarine.state().horizontal_position, 5);
 
-        submarine.forward(2);
-        assert_eq!(submarine.horizontal_position, 7);
+        submarine.apply(Command::Forward(2)).unwrap();
+        assert_eq!(submarine.state().horizontal_position, 7);
+        assert_eq!(submarine.state().vertical_position, 0);
     }
 
     #[test]
     fn test_move_submarine_down() {
         let mut submarine = Submarine::new(0, 0);
 
-        submarine.down(3);
-        assert_eq!(submarine.vertical_position, -3);
+        submarine.apply(Command::Down(3)).unwrap();
+        assert_eq!(submarine.state().vertical_position, 3);
 
-        submarine.down(2);
-        assert_eq!(submarine.vertical_position, -5);
+        submarine.apply(Command::Down(2)).unwrap();
+        assert_eq!(submarine.state().vertical_position, 5);
     }
 
     #[test]
     fn test_move_submarine_up() {
         let mut submarine = Submarine::new(0, 0);
 
-        submarine.up(8);
-        assert_eq!(submarine.vertical_position, 8);
+        submarine.apply(Command::Up(8)).unwrap();
+        assert_eq!(submarine.state().vertical_position, -8);
 
-        submarine.up(10);
-        assert_eq!(submarine.vertical_position, 18);
+        submarine.apply(Command::Up(10)).unwrap();
+        assert_eq!(submarine.state().vertical_position, -18);
     }
 
     #[test]
     fn test_process_instruction() {
         let mut submarine = Submarine::new(0, 0);
 
-        submarine.process_instruction("forward 5".to_string());
-        assert_eq!(submarine.horizontal_position, 5);
+        submarine.process_instruction("forward 5").unwrap();
+        assert_eq!(submarine.state().horizontal_position, 5);
 
-        submarine.process_instruction("up 5".to_string());
-        assert_eq!(submarine.vertical_position, 5);
+        submarine.process_instruction("up 5").unwrap();
+        assert_eq!(submarine.state().vertical_position, -5);
 
-        submarine.process_instruction("down 10".to_string());
-        assert_eq!(submarine.vertical_position, -5);
+        submarine.process_instruction("down 10").unwrap();
+        assert_eq!(submarine.state().vertical_position, 5);
     }
 
     #[test]
@@ -112,7 +114,7 @@ mod tests_day2a {
         assert_eq!(submarine.multiply_positions(), 50);
 
         let submarine2 = Submarine::new(5, -20);
-        assert_eq!(submarine2.multiply_positions(), 100);
+        assert_eq!(submarine2.multiply_positions(), -100);
     }
 
     #[test]
@@ -126,7 +128,39 @@ mod tests_day2a {
             "forward 2".to_string(),
         ];
         let mut submarine = Submarine::new(0, 0);
-        submarine.process_instructions(instructions);
+        submarine.process_instructions(instructions).unwrap();
         assert_eq!(submarine.multiply_positions(), 150)
     }
+
+    #[test]
+    fn test_invalid_instructions_are_rejected() {
+        let instructions = vec!["forward 5".to_string(), "down".to_string()];
+        let mut submarine = Submarine::new(0, 0);
+        let error = submarine.process_instructions(instructions).unwrap_err();
+        match error {
+            SubmarineError::Command(error) => assert_eq!(error.line_number, 2),
+            _ => panic!("expected a command error"),
+        }
+        assert_eq!(submarine.state().horizontal_position, 0);
+    }
+
+    #[test]
+    fn test_overflow_is_reported() {
+        let mut submarine = Submarine::new(i64::MAX, 0);
+        assert_eq!(
+            submarine.apply(Command::Forward(1)),
+            Err(MovementError::Overflow)
+        );
+        assert_eq!(submarine.state().horizontal_position, i64::MAX);
+    }
+
+    #[test]
+    fn test_large_positions_do_not_wrap() {
+        let mut submarine = Submarine::new(i64::from(u32::MAX), 0);
+        submarine.apply(Command::Down(i32::MAX)).unwrap();
+        assert_eq!(
+            submarine.multiply_positions(),
+            i128::from(u32::MAX) * i128::from(i32::MAX)
+        );
+    }
 }
@@ -0,0 +1,230 @@
+use std::{error, fmt, str::FromStr};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    Forward(u32),
+    Up(i32),
+    Down(i32),
+    Backward(u32),
+    Hold(u32),
+    TurnLeft(u32),
+    TurnRight(u32),
+}
+
+impl fmt::Display for Command {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Command::Forward(count) => write!(f, "forward {}", count),
+            Command::Up(count) => write!(f, "up {}", count),
+            Command::Down(count) => write!(f, "down {}", count),
+            Command::Backward(count) => write!(f, "backward {}", count),
+            Command::Hold(count) => write!(f, "hold {}", count),
+            Command::TurnLeft(degrees) => write!(f, "turn left {}", degrees),
+            Command::TurnRight(degrees) => write!(f, "turn right {}", degrees),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseCommandError {
+    Empty,
+    UnknownVerb(String),
+    MissingAmount(String),
+    InvalidAmount(String),
+    NegativeAmount(String),
+    InvalidDirection(String),
+    TrailingInput(String),
+}
+
+impl fmt::Display for ParseCommandError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseCommandError::Empty => write!(f, "empty command"),
+            ParseCommandError::UnknownVerb(verb) => write!(f, "unknown command {:?}", verb),
+            ParseCommandError::MissingAmount(verb) => write!(f, "missing amount for {:?}", verb),
+            ParseCommandError::InvalidAmount(amount) => write!(f, "invalid amount {:?}", amount),
+            ParseCommandError::NegativeAmount(amount) => {
+                write!(f, "negative amount {:?}", amount)
+            }
+            ParseCommandError::InvalidDirection(direction) => {
+                write!(f, "invalid turn direction {:?}", direction)
+            }
+            ParseCommandError::TrailingInput(input) => {
+                write!(f, "unexpected input {:?} after amount", input)
+            }
+        }
+    }
+}
+
+impl error::Error for ParseCommandError {}
+
+fn parse_amount<T: TryFrom<i64>>(amount: &str) -> Result<T, ParseCommandError> {
+    let value: i64 = amount
+        .parse()
+        .map_err(|_| ParseCommandError::InvalidAmount(amount.to_string()))?;
+    if value < 0 {
+        return Err(ParseCommandError::NegativeAmount(amount.to_string()));
+    }
+    T::try_from(value).map_err(|_| ParseCommandError::InvalidAmount(amount.to_string()))
+}
+
+impl FromStr for Command {
+    type Err = ParseCommandError;
+
+    fn from_str(instruction: &str) -> Result<Self, Self::Err> {
+        let tokens: Vec<&str> = instruction.split_whitespace().collect();
+        let (verb, operands) = tokens.split_first().ok_or(ParseCommandError::Empty)?;
+        if let Some(trailing) = operands.get(verb_arity(verb)) {
+            return Err(ParseCommandError::TrailingInput(trailing.to_string()));
+        }
+        parse_verb(verb, operands)
+    }
+}
+
+pub(crate) fn is_verb(word: &str) -> bool {
+    matches!(
+        word.to_lowercase().as_ref(),
+        "forward" | "up" | "down" | "backward" | "hold" | "turn"
+    )
+}
+
+pub(crate) fn verb_arity(verb: &str) -> usize {
+    if verb.eq_ignore_ascii_case("turn") {
+        2
+    } else {
+        1
+    }
+}
+
+pub(crate) fn parse_verb(verb: &str, operands: &[&str]) -> Result<Command, ParseCommandError> {
+    let verb = verb.to_lowercase();
+    let amount = || {
+        operands
+            .get(verb_arity(&verb) - 1)
+            .copied()
+            .ok_or_else(|| ParseCommandError::MissingAmount(verb.clone()))
+    };
+
+    match verb.as_ref() {
+        "forward" => Ok(Command::Forward(parse_amount(amount()?)?)),
+        "up" => Ok(Command::Up(parse_amount(amount()?)?)),
+        "down" => Ok(Command::Down(parse_amount(amount()?)?)),
+        "backward" => Ok(Command::Backward(parse_amount(amount()?)?)),
+        "hold" => Ok(Command::Hold(parse_amount(amount()?)?)),
+        "turn" => {
+            let direction = operands
+                .first()
+                .ok_or_else(|| ParseCommandError::MissingAmount(verb.clone()))?;
+            let degrees = parse_amount(amount()?)?;
+            match direction.to_lowercase().as_ref() {
+                "left" => Ok(Command::TurnLeft(degrees)),
+                "right" => Ok(Command::TurnRight(degrees)),
+                _ => Err(ParseCommandError::InvalidDirection(direction.to_string())),
+            }
+        }
+        _ => Err(ParseCommandError::UnknownVerb(verb.clone())),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommandError {
+    pub line_number: usize,
+    pub kind: ParseCommandError,
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line_number, self.kind)
+    }
+}
+
+impl error::Error for CommandError {}
+
+pub fn parse_commands<S: AsRef<str>>(instructions: &[S]) -> Result<Vec<Command>, CommandError> {
+    instructions
+        .iter()
+        .enumerate()
+        .map(|(index, instruction)| {
+            instruction.as_ref().parse().map_err(|kind| CommandError {
+                line_number: index + 1,
+                kind,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests_command {
+    use super::*;
+
+    #[test]
+    fn test_parse_command() {
+        assert_eq!("forward 5".parse(), Ok(Command::Forward(5)));
+        assert_eq!("Up 3".parse(), Ok(Command::Up(3)));
+        assert_eq!("down  8".parse(), Ok(Command::Down(8)));
+        assert_eq!("backward 2".parse(), Ok(Command::Backward(2)));
+        assert_eq!("hold 4".parse(), Ok(Command::Hold(4)));
+        assert_eq!("turn left 90".parse(), Ok(Command::TurnLeft(90)));
+        assert_eq!("Turn Right 45".parse(), Ok(Command::TurnRight(45)));
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        for command in [
+            Command::Forward(5),
+            Command::Up(3),
+            Command::Down(8),
+            Command::Backward(2),
+            Command::Hold(4),
+            Command::TurnLeft(90),
+            Command::TurnRight(30),
+        ] {
+            assert_eq!(command.to_string().parse(), Ok(command));
+        }
+    }
+
+    #[test]
+    fn test_parse_command_errors() {
+        assert_eq!("".parse::<Command>(), Err(ParseCommandError::Empty));
+        assert_eq!(
+            "sideways 5".parse::<Command>(),
+            Err(ParseCommandError::UnknownVerb("sideways".to_string()))
+        );
+        assert_eq!(
+            "forward".parse::<Command>(),
+            Err(ParseCommandError::MissingAmount("forward".to_string()))
+        );
+        assert_eq!(
+            "up -3".parse::<Command>(),
+            Err(ParseCommandError::NegativeAmount("-3".to_string()))
+        );
+        assert_eq!(
+            "down many".parse::<Command>(),
+            Err(ParseCommandError::InvalidAmount("many".to_string()))
+        );
+        assert_eq!(
+            "down 99999999999".parse::<Command>(),
+            Err(ParseCommandError::InvalidAmount("99999999999".to_string()))
+        );
+        assert_eq!(
+            "down 3 4".parse::<Command>(),
+            Err(ParseCommandError::TrailingInput("4".to_string()))
+        );
+        assert_eq!(
+            "turn 90".parse::<Command>(),
+            Err(ParseCommandError::MissingAmount("turn".to_string()))
+        );
+        assert_eq!(
+            "turn around 90".parse::<Command>(),
+            Err(ParseCommandError::InvalidDirection("around".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_parse_commands_reports_line_number() {
+        let instructions = vec!["forward 5", "down 5", "dive 2"];
+        let error = parse_commands(&instructions).unwrap_err();
+        assert_eq!(error.line_number, 3);
+        assert_eq!(error.to_string(), "line 3: unknown command \"dive\"");
+    }
+}
@@ -0,0 +1,328 @@
+use crate::{
+    command::{Command, CommandError},
+    movement::{MovementModel, SubmarineState},
+    Submarine, SubmarineError,
+};
+use std::{
+    collections::{BTreeMap, HashMap},
+    error, fmt,
+    num::NonZeroUsize,
+    thread,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FleetError {
+    MissingSubmarineId { line_number: usize },
+    Command(CommandError),
+}
+
+impl fmt::Display for FleetError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FleetError::MissingSubmarineId { line_number } => {
+                write!(f, "line {}: missing submarine id", line_number)
+            }
+            FleetError::Command(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl error::Error for FleetError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubmarineReport {
+    pub id: String,
+    pub state: SubmarineState,
+    pub multiplied_positions: i128,
+    pub error: Option<SubmarineError>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Collision {
+    pub step: usize,
+    pub horizontal_position: i64,
+    pub lateral_position: i64,
+    pub vertical_position: i64,
+    pub submarines: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FleetReport {
+    pub submarines: Vec<SubmarineReport>,
+    pub collisions: Vec<Collision>,
+}
+
+struct Run {
+    state: SubmarineState,
+    error: Option<SubmarineError>,
+    multiplied_positions: i128,
+    positions: Vec<SubmarineState>,
+}
+
+fn run(model: Box<dyn MovementModel>, commands: &[Command]) -> Run {
+    let mut submarine = Submarine::with_model(model);
+    submarine.record_trajectory();
+    let error = submarine.apply_all(commands).err();
+    Run {
+        state: submarine.state(),
+        error,
+        multiplied_positions: submarine.multiply_positions(),
+        positions: submarine
+            .trajectory()
+            .map(|trajectory| {
+                trajectory
+                    .points()
+                    .iter()
+                    .map(|point| point.state)
+                    .collect()
+            })
+            .unwrap_or_default(),
+    }
+}
+
+pub struct Fleet {
+    streams: Vec<(String, Vec<Command>)>,
+}
+
+impl Fleet {
+    // Each line is `<submarine id>: <command>`. Lines for different submarines
+    // may be interleaved; each submarine keeps its own commands in file order.
+    pub fn parse<S: AsRef<str>>(lines: &[S]) -> Result<Self, FleetError> {
+        let mut streams: Vec<(String, Vec<Command>)> = Vec::new();
+        let mut indices: HashMap<String, usize> = HashMap::new();
+        for (index, line) in lines.iter().enumerate() {
+            let line_number = index + 1;
+            let line = line.as_ref().trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (id, instruction) = match line.split_once(':') {
+                Some((id, instruction)) if !id.trim().is_empty() => (id.trim(), instruction),
+                _ => return Err(FleetError::MissingSubmarineId { line_number }),
+            };
+            let command = instruction
+                .parse()
+                .map_err(|kind| FleetError::Command(CommandError { line_number, kind }))?;
+            let stream = *indices.entry(id.to_string()).or_insert_with(|| {
+                streams.push((id.to_string(), Vec::new()));
+                streams.len() - 1
+            });
+            streams[stream].1.push(command);
+        }
+        Ok(Fleet { streams })
+    }
+
+    pub fn ids(&self) -> impl Iterator<Item = &str> {
+        self.streams.iter().map(|(id, _)| id.as_str())
+    }
+
+    pub fn simulate<F>(&self, model: F) -> FleetReport
+    where
+        F: Fn() -> Box<dyn MovementModel> + Sync,
+    {
+        // One worker per core, each running a contiguous chunk of the streams,
+        // so a large fleet does not need a thread per submarine.
+        let model = &model;
+        let workers = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+        let chunk_size = self.streams.len().div_ceil(workers).max(1);
+        let runs: Vec<Run> = thread::scope(|scope| {
+            let handles: Vec<_> = self
+                .streams
+                .chunks(chunk_size)
+                .map(|chunk| {
+                    scope.spawn(move || {
+                        chunk
+                            .iter()
+                            .map(|(_, commands)| run(model(), commands))
+                            .collect::<Vec<Run>>()
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .flat_map(|handle| handle.join().expect("submarine simulation panicked"))
+                .collect()
+        });
+
+        let collisions = self.find_collisions(&runs);
+        let submarines = self
+            .streams
+            .iter()
+            .zip(runs)
+            .map(|((id, _), run)| SubmarineReport {
+                id: id.clone(),
+                state: run.state,
+                multiplied_positions: run.multiplied_positions,
+                error: run.error,
+            })
+            .collect();
+        FleetReport {
+            submarines,
+            collisions,
+        }
+    }
+
+    // Step N is the position after each submarine's Nth command. A submarine
+    // that has run out of commands, or stopped on an error, stays parked where
+    // it is; a collision is only reported while at least one of the submarines
+    // involved is still moving.
+    fn find_collisions(&self, runs: &[Run]) -> Vec<Collision> {
+        let steps = runs
+            .iter()
+            .map(|run| run.positions.len())
+            .max()
+            .unwrap_or(0);
+        let mut collisions = Vec::new();
+        for step in 1..=steps {
+            let mut occupied: BTreeMap<(i64, i64, i64), Vec<usize>> = BTreeMap::new();
+            for (index, run) in runs.iter().enumerate() {
+                let state = run.positions.get(step - 1).unwrap_or(&run.state);
+                occupied
+                    .entry((
+                        state.horizontal_position,
+                        state.lateral_position,
+                        state.vertical_position,
+                    ))
+                    .or_default()
+                    .push(index);
+            }
+            for ((horizontal_position, lateral_position, vertical_position), members) in occupied {
+                if members.len() < 2 || members.iter().all(|&i| runs[i].positions.len() < step) {
+                    continue;
+                }
+                collisions.push(Collision {
+                    step,
+                    horizontal_position,
+                    lateral_position,
+                    vertical_position,
+                    submarines: members
+                        .into_iter()
+                        .map(|i| self.streams[i].0.clone())
+                        .collect(),
+                });
+            }
+        }
+        collisions
+    }
+}
+
+#[cfg(test)]
+mod tests_fleet {
+    use super::*;
+    use crate::movement::{AimModel, DirectModel, MovementError};
+
+    fn get_sample_fleet() -> Vec<&'static str> {
+        vec![
+            "# four submarines on interleaved lines",
+            "alpha: forward 5",
+            "bravo: down 5",
+            "charlie: forward 6",
+            "echo: forward 3",
+            "alpha: down 5",
+            "bravo: forward 5",
+            "echo: forward 3",
+            "",
+            "alpha: forward 1",
+            "bravo: forward 1",
+        ]
+    }
+
+    #[test]
+    fn test_parse_fleet() {
+        let fleet = Fleet::parse(&get_sample_fleet()).unwrap();
+        assert_eq!(
+            fleet.ids().collect::<Vec<_>>(),
+            vec!["alpha", "bravo", "charlie", "echo"]
+        );
+        assert_eq!(
+            fleet.streams[1].1,
+            vec![Command::Down(5), Command::Forward(5), Command::Forward(1)]
+        );
+    }
+
+    #[test]
+    fn test_parse_fleet_errors() {
+        assert_eq!(
+            Fleet::parse(&["alpha: forward 5", "forward 5"]).err(),
+            Some(FleetError::MissingSubmarineId { line_number: 2 })
+        );
+        let error = Fleet::parse(&["alpha: sideways 2"]).err().unwrap();
+        assert_eq!(error.to_string(), "line 1: unknown command \"sideways\"");
+    }
+
+    #[test]
+    fn test_simulate_fleet() {
+        let fleet = Fleet::parse(&get_sample_fleet()).unwrap();
+        let report = fleet.simulate(|| Box::new(DirectModel));
+
+        let alpha = &report.submarines[0];
+        assert_eq!(alpha.id, "alpha");
+        assert_eq!(alpha.multiplied_positions, 30);
+        assert!(report.submarines.iter().all(|sub| sub.error.is_none()));
+
+        let collisions: Vec<(usize, i64, i64, Vec<String>)> = report
+            .collisions
+            .into_iter()
+            .map(|c| {
+                (
+                    c.step,
+                    c.horizontal_position,
+                    c.vertical_position,
+                    c.submarines,
+                )
+            })
+            .collect();
+        let ids = |ids: &[&str]| ids.iter().map(|id| id.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            collisions,
+            vec![
+                (2, 5, 5, ids(&["alpha", "bravo"])),
+                (2, 6, 0, ids(&["charlie", "echo"])),
+                (3, 6, 5, ids(&["alpha", "bravo"])),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_simulate_fleet_reports_errors() {
+        let fleet = Fleet::parse(&[
+            "deep: down 2147483647",
+            "deep: forward 4294967295",
+            "deep: forward 4294967295",
+            "shallow: forward 1",
+        ])
+        .unwrap();
+        let report = fleet.simulate(|| Box::new(AimModel));
+        assert!(matches!(
+            report.submarines[0].error,
+            Some(SubmarineError::Movement {
+                command_index: 2,
+                error: MovementError::Overflow,
+                ..
+            })
+        ));
+        assert_eq!(report.submarines[1].state.horizontal_position, 1);
+    }
+
+    #[test]
+    fn test_simulate_more_submarines_than_cores() {
+        let count = thread::available_parallelism().map_or(1, NonZeroUsize::get) * 4 + 3;
+        let lines: Vec<String> = (0..count)
+            .flat_map(|i| {
+                [
+                    format!("sub{}: down {}", i, i + 1),
+                    format!("sub{}: forward 1", i),
+                ]
+            })
+            .collect();
+        let report = Fleet::parse(&lines)
+            .unwrap()
+            .simulate(|| Box::new(DirectModel));
+        assert_eq!(report.submarines.len(), count);
+        for (i, submarine) in report.submarines.iter().enumerate() {
+            assert_eq!(submarine.id, format!("sub{}", i));
+            assert_eq!(submarine.multiplied_positions, i as i128 + 1);
+        }
+        assert!(report.collisions.is_empty());
+    }
+}
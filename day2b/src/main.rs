@@ -1,22 +1,161 @@
-use ::cmilbert_aoc_day2b::Submarine;
+use ::cmilbert_aoc_day2b::{
+    built_in_models, compare_models, model_constructor, parse_commands, Command, Fleet,
+    ModelConstructor, Submarine,
+};
 
 use std::{
-    fs::File,
-    io::{prelude::*, BufReader},
-    path::Path,
+    env, fmt,
+    fs::{self, File},
+    io::{prelude::*, BufReader, BufWriter},
+    process,
 };
 
-fn read_lines_from_input_file(filename: impl AsRef<Path>) -> Vec<String> {
-    let file = File::open(filename).expect("no such file");
-    let buf = BufReader::new(file);
-    buf.lines()
-        .map(|l| l.expect("Could not parse line"))
-        .collect()
+// Every failure is reported the same way: what failed, then why, then exit 1.
+fn exit_with_error(context: &str, error: impl fmt::Display) -> ! {
+    eprintln!("{}: {}", context, error);
+    process::exit(1);
+}
+
+fn read_lines_from_input_file(filename: &str) -> Vec<String> {
+    File::open(filename)
+        .and_then(|file| BufReader::new(file).lines().collect())
+        .unwrap_or_else(|error| exit_with_error(filename, error))
+}
+
+fn model_constructor_or_exit(name: &str) -> ModelConstructor {
+    model_constructor(name).unwrap_or_else(|| {
+        let names: Vec<String> = built_in_models()
+            .iter()
+            .map(|model| model.name().to_string())
+            .collect();
+        eprintln!(
+            "unknown movement model {:?}; expected one of {}",
+            name,
+            names.join(", ")
+        );
+        process::exit(1);
+    })
 }
 
 fn main() {
-    let mut submarine = Submarine::new(0, 0, 0);
-    let instructions = read_lines_from_input_file("input.txt");
-    submarine.process_instructions(instructions);
-    println!("Final position: {}", submarine.multiply_positions());
+    let args: Vec<String> = env::args().collect();
+
+    match args.get(1).map(String::as_str) {
+        Some("--compare") => {
+            let instructions = read_lines_from_input_file("input.txt");
+            let commands = parse_commands(&instructions)
+                .unwrap_or_else(|error| exit_with_error("input.txt", error));
+            for outcome in compare_models(&commands) {
+                match outcome.error {
+                    Some(error) => println!("{} model failed: {}", outcome.model, error),
+                    None => println!(
+                        "{} model final position: {}",
+                        outcome.model, outcome.multiplied_positions
+                    ),
+                }
+            }
+        }
+        Some("--trajectory") => {
+            let output_file_name = args.get(2).map(String::as_str).unwrap_or("trajectory.csv");
+            let mut submarine = Submarine::new(0, 0, 0);
+            submarine.record_trajectory();
+            submarine
+                .process_instructions(read_lines_from_input_file("input.txt"))
+                .unwrap_or_else(|error| exit_with_error("input.txt", error));
+            let trajectory = submarine.trajectory().unwrap();
+            File::create(output_file_name)
+                .and_then(|output_file| trajectory.write_csv(BufWriter::new(output_file)))
+                .unwrap_or_else(|error| exit_with_error(output_file_name, error));
+            println!("Max depth: {}", trajectory.max_depth());
+            println!("Total distance: {:.2}", trajectory.total_distance());
+        }
+        Some("--script") => {
+            let script_file_name = args.get(2).map(String::as_str).unwrap_or("input.sub");
+            let source = fs::read_to_string(script_file_name)
+                .unwrap_or_else(|error| exit_with_error(script_file_name, error));
+            let mut submarine = Submarine::new(0, 0, 0);
+            submarine
+                .run_script(&source)
+                .unwrap_or_else(|error| exit_with_error(script_file_name, error));
+            println!("Final position: {}", submarine.multiply_positions());
+        }
+        Some("--plan") => {
+            let target = match (args.get(2), args.get(3)) {
+                (Some(horizontal), Some(depth)) => {
+                    match (horizontal.parse::<i64>(), depth.parse::<i64>()) {
+                        (Ok(horizontal), Ok(depth)) => [horizontal, depth],
+                        _ => {
+                            eprintln!("plan targets must be whole numbers");
+                            process::exit(1);
+                        }
+                    }
+                }
+                _ => {
+                    eprintln!("usage: --plan <horizontal> <depth> [model]");
+                    process::exit(1);
+                }
+            };
+            let name = args.get(4).map(String::as_str).unwrap_or("aim");
+            let mut submarine = Submarine::with_model(model_constructor_or_exit(name)());
+            let commands = submarine
+                .plan_course(target[0], target[1])
+                .unwrap_or_else(|error| exit_with_error("plan", error));
+            let instructions: Vec<String> = commands.iter().map(Command::to_string).collect();
+            for instruction in &instructions {
+                println!("{}", instruction);
+            }
+            submarine
+                .process_instructions(instructions)
+                .unwrap_or_else(|error| exit_with_error("plan", error));
+            println!("Final position: {}", submarine.multiply_positions());
+        }
+        Some("--fleet") => {
+            let fleet_file_name = args.get(2).map(String::as_str).unwrap_or("fleet.txt");
+            let name = args.get(3).map(String::as_str).unwrap_or("aim");
+            let model = model_constructor_or_exit(name);
+            let fleet = Fleet::parse(&read_lines_from_input_file(fleet_file_name))
+                .unwrap_or_else(|error| exit_with_error(fleet_file_name, error));
+            let report = fleet.simulate(model);
+            for submarine in &report.submarines {
+                match &submarine.error {
+                    Some(error) => println!("{} failed: {}", submarine.id, error),
+                    None => println!(
+                        "{} final position: {}",
+                        submarine.id, submarine.multiplied_positions
+                    ),
+                }
+            }
+            for collision in &report.collisions {
+                println!(
+                    "Collision at step {} ({}, {}, {}): {}",
+                    collision.step,
+                    collision.horizontal_position,
+                    collision.lateral_position,
+                    collision.vertical_position,
+                    collision.submarines.join(", ")
+                );
+            }
+        }
+        Some("--model") => {
+            let name = args.get(2).map(String::as_str).unwrap_or("aim");
+            let mut submarine = Submarine::with_model(model_constructor_or_exit(name)());
+            submarine
+                .process_instructions(read_lines_from_input_file("input.txt"))
+                .unwrap_or_else(|error| exit_with_error("input.txt", error));
+            let state = submarine.state();
+            println!("Final position: {}", submarine.multiply_positions());
+            println!(
+                "Distance from origin: {} (Manhattan), {:.2} (Euclidean)",
+                state.manhattan_distance(),
+                state.euclidean_distance()
+            );
+        }
+        _ => {
+            let mut submarine = Submarine::new(0, 0, 0);
+            submarine
+                .process_instructions(read_lines_from_input_file("input.txt"))
+                .unwrap_or_else(|error| exit_with_error("input.txt", error));
+            println!("Final position: {}", submarine.multiply_positions());
+        }
+    }
 }
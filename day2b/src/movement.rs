@@ -0,0 +1,413 @@
+use crate::{
+    command::Command,
+    planner::{plan_aim, plan_direct, PlanError},
+};
+use std::{error, fmt};
+
+// Vertical position is depth: it grows as the submarine descends, so both
+// built-in models agree on the sign of the final product. Lateral position
+// and heading (degrees clockwise from the horizontal axis) are only moved by
+// the heading model.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SubmarineState {
+    pub horizontal_position: i64,
+    pub vertical_position: i64,
+    pub aim: i64,
+    pub lateral_position: i64,
+    pub heading: i64,
+}
+
+impl SubmarineState {
+    pub fn manhattan_distance(&self) -> u128 {
+        u128::from(self.horizontal_position.unsigned_abs())
+            + u128::from(self.lateral_position.unsigned_abs())
+            + u128::from(self.vertical_position.unsigned_abs())
+    }
+
+    pub fn euclidean_distance(&self) -> f64 {
+        let horizontal = self.horizontal_position as f64;
+        let lateral = self.lateral_position as f64;
+        let vertical = self.vertical_position as f64;
+        (horizontal * horizontal + lateral * lateral + vertical * vertical).sqrt()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MovementError {
+    Overflow,
+    TooShallow { depth: i64, minimum_depth: i64 },
+    TooDeep { depth: i64, maximum_depth: i64 },
+}
+
+impl fmt::Display for MovementError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MovementError::Overflow => write!(f, "position overflowed"),
+            MovementError::TooShallow {
+                depth,
+                minimum_depth,
+            } => write!(
+                f,
+                "depth {} is shallower than the minimum depth {}",
+                depth, minimum_depth
+            ),
+            MovementError::TooDeep {
+                depth,
+                maximum_depth,
+            } => write!(
+                f,
+                "depth {} is deeper than the maximum depth {}",
+                depth, maximum_depth
+            ),
+        }
+    }
+}
+
+impl error::Error for MovementError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DepthLimits {
+    pub minimum_depth: Option<i64>,
+    pub maximum_depth: Option<i64>,
+}
+
+impl DepthLimits {
+    pub fn surface_only() -> Self {
+        DepthLimits {
+            minimum_depth: Some(0),
+            maximum_depth: None,
+        }
+    }
+
+    pub fn check(&self, state: &SubmarineState) -> Result<(), MovementError> {
+        let depth = state.vertical_position;
+        if let Some(minimum_depth) = self.minimum_depth {
+            if depth < minimum_depth {
+                return Err(MovementError::TooShallow {
+                    depth,
+                    minimum_depth,
+                });
+            }
+        }
+        if let Some(maximum_depth) = self.maximum_depth {
+            if depth > maximum_depth {
+                return Err(MovementError::TooDeep {
+                    depth,
+                    maximum_depth,
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+pub trait MovementModel {
+    fn name(&self) -> &str;
+    fn apply(&self, state: &mut SubmarineState, command: Command) -> Result<(), MovementError>;
+
+    fn plan(
+        &self,
+        _start: SubmarineState,
+        _target_horizontal_position: i64,
+        _target_depth: i64,
+    ) -> Result<Vec<Command>, PlanError> {
+        Err(PlanError::Unsupported(self.name().to_string()))
+    }
+}
+
+fn checked(value: Option<i64>) -> Result<i64, MovementError> {
+    value.ok_or(MovementError::Overflow)
+}
+
+pub struct DirectModel;
+
+impl MovementModel for DirectModel {
+    fn name(&self) -> &str {
+        "direct"
+    }
+
+    fn plan(
+        &self,
+        start: SubmarineState,
+        target_horizontal_position: i64,
+        target_depth: i64,
+    ) -> Result<Vec<Command>, PlanError> {
+        plan_direct(start, target_horizontal_position, target_depth)
+    }
+
+    fn apply(&self, state: &mut SubmarineState, command: Command) -> Result<(), MovementError> {
+        let mut next = *state;
+        match command {
+            Command::Forward(count) => {
+                next.horizontal_position =
+                    checked(next.horizontal_position.checked_add(count.into()))?
+            }
+            Command::Down(count) => {
+                next.vertical_position = checked(next.vertical_position.checked_add(count.into()))?
+            }
+            Command::Up(count) => {
+                next.vertical_position = checked(next.vertical_position.checked_sub(count.into()))?
+            }
+            Command::Backward(count) => {
+                next.horizontal_position =
+                    checked(next.horizontal_position.checked_sub(count.into()))?
+            }
+            // The 2D models have no heading to turn.
+            Command::Hold(_) | Command::TurnLeft(_) | Command::TurnRight(_) => {}
+        }
+        *state = next;
+        Ok(())
+    }
+}
+
+pub struct AimModel;
+
+impl MovementModel for AimModel {
+    fn name(&self) -> &str {
+        "aim"
+    }
+
+    fn plan(
+        &self,
+        start: SubmarineState,
+        target_horizontal_position: i64,
+        target_depth: i64,
+    ) -> Result<Vec<Command>, PlanError> {
+        plan_aim(start, target_horizontal_position, target_depth)
+    }
+
+    fn apply(&self, state: &mut SubmarineState, command: Command) -> Result<(), MovementError> {
+        let mut next = *state;
+        match command {
+            Command::Forward(count) => {
+                let dive = checked(i64::from(count).checked_mul(next.aim))?;
+                next.horizontal_position =
+                    checked(next.horizontal_position.checked_add(count.into()))?;
+                next.vertical_position = checked(next.vertical_position.checked_add(dive))?;
+            }
+            Command::Down(count) => next.aim = checked(next.aim.checked_add(count.into()))?,
+            Command::Up(count) => next.aim = checked(next.aim.checked_sub(count.into()))?,
+            Command::Backward(count) => {
+                let rise = checked(i64::from(count).checked_mul(next.aim))?;
+                next.horizontal_position =
+                    checked(next.horizontal_position.checked_sub(count.into()))?;
+                next.vertical_position = checked(next.vertical_position.checked_sub(rise))?;
+            }
+            // The 2D models have no heading to turn.
+            Command::Hold(_) | Command::TurnLeft(_) | Command::TurnRight(_) => {}
+        }
+        *state = next;
+        Ok(())
+    }
+}
+
+// Pitch comes from aim as in the aim model, while forward and backward travel
+// along the current heading. Each leg's horizontal and lateral displacement is
+// rounded to whole units, so turns that are not multiples of 90 degrees
+// accumulate rounding error over many legs.
+pub struct HeadingModel;
+
+impl HeadingModel {
+    fn travel(state: &mut SubmarineState, distance: i64) -> Result<(), MovementError> {
+        let radians = (state.heading as f64).to_radians();
+        let horizontal = (distance as f64 * radians.cos()).round() as i64;
+        let lateral = (distance as f64 * radians.sin()).round() as i64;
+        let dive = checked(distance.checked_mul(state.aim))?;
+        state.horizontal_position = checked(state.horizontal_position.checked_add(horizontal))?;
+        state.lateral_position = checked(state.lateral_position.checked_add(lateral))?;
+        state.vertical_position = checked(state.vertical_position.checked_add(dive))?;
+        Ok(())
+    }
+}
+
+impl MovementModel for HeadingModel {
+    fn name(&self) -> &str {
+        "heading"
+    }
+
+    fn apply(&self, state: &mut SubmarineState, command: Command) -> Result<(), MovementError> {
+        let mut next = *state;
+        match command {
+            Command::Forward(count) => HeadingModel::travel(&mut next, count.into())?,
+            Command::Backward(count) => HeadingModel::travel(&mut next, -i64::from(count))?,
+            Command::Down(count) => next.aim = checked(next.aim.checked_add(count.into()))?,
+            Command::Up(count) => next.aim = checked(next.aim.checked_sub(count.into()))?,
+            Command::TurnLeft(degrees) => {
+                next.heading = (next.heading - i64::from(degrees)).rem_euclid(360)
+            }
+            Command::TurnRight(degrees) => {
+                next.heading = (next.heading + i64::from(degrees)).rem_euclid(360)
+            }
+            Command::Hold(_) => {}
+        }
+        *state = next;
+        Ok(())
+    }
+}
+
+pub type ModelConstructor = fn() -> Box<dyn MovementModel>;
+
+const BUILT_IN_MODELS: [ModelConstructor; 3] = [
+    || Box::new(DirectModel),
+    || Box::new(AimModel),
+    || Box::new(HeadingModel),
+];
+
+pub fn built_in_models() -> Vec<Box<dyn MovementModel>> {
+    BUILT_IN_MODELS.iter().map(|new| new()).collect()
+}
+
+// For callers that need a fresh model per submarine, such as a fleet.
+pub fn model_constructor(name: &str) -> Option<ModelConstructor> {
+    BUILT_IN_MODELS
+        .into_iter()
+        .find(|new| new().name().eq_ignore_ascii_case(name))
+}
+
+pub fn model_from_name(name: &str) -> Option<Box<dyn MovementModel>> {
+    model_constructor(name).map(|new| new())
+}
+
+#[cfg(test)]
+mod tests_movement {
+    use super::*;
+
+    #[test]
+    fn test_direct_model() {
+        let mut state = SubmarineState::default();
+        DirectModel.apply(&mut state, Command::Down(5)).unwrap();
+        DirectModel.apply(&mut state, Command::Forward(3)).unwrap();
+        DirectModel.apply(&mut state, Command::Up(2)).unwrap();
+        DirectModel.apply(&mut state, Command::Hold(4)).unwrap();
+        assert_eq!(state.horizontal_position, 3);
+        assert_eq!(state.vertical_position, 3);
+
+        DirectModel.apply(&mut state, Command::Backward(1)).unwrap();
+        assert_eq!(state.horizontal_position, 2);
+        assert_eq!(state.vertical_position, 3);
+        assert_eq!(state.aim, 0);
+    }
+
+    #[test]
+    fn test_aim_model() {
+        let mut state = SubmarineState::default();
+        AimModel.apply(&mut state, Command::Down(5)).unwrap();
+        AimModel.apply(&mut state, Command::Forward(3)).unwrap();
+        AimModel.apply(&mut state, Command::Up(2)).unwrap();
+        AimModel.apply(&mut state, Command::Hold(4)).unwrap();
+        assert_eq!(state.horizontal_position, 3);
+        assert_eq!(state.vertical_position, 15);
+        assert_eq!(state.aim, 3);
+
+        AimModel.apply(&mut state, Command::Backward(2)).unwrap();
+        assert_eq!(state.horizontal_position, 1);
+        assert_eq!(state.vertical_position, 9);
+    }
+
+    #[test]
+    fn test_heading_model() {
+        let mut state = SubmarineState::default();
+        let commands = [
+            Command::Down(2),
+            Command::Forward(5),
+            Command::TurnRight(90),
+            Command::Forward(3),
+            Command::TurnLeft(180),
+            Command::Up(2),
+            Command::Forward(10),
+            Command::TurnLeft(90),
+            Command::Backward(4),
+        ];
+        for command in commands {
+            HeadingModel.apply(&mut state, command).unwrap();
+        }
+        assert_eq!(state.horizontal_position, 9);
+        assert_eq!(state.lateral_position, -7);
+        assert_eq!(state.vertical_position, 16);
+        assert_eq!(state.heading, 180);
+        assert_eq!(state.manhattan_distance(), 32);
+        assert_eq!(state.euclidean_distance(), (81.0f64 + 49.0 + 256.0).sqrt());
+    }
+
+    #[test]
+    fn test_heading_model_diagonal() {
+        let mut state = SubmarineState::default();
+        HeadingModel
+            .apply(&mut state, Command::TurnLeft(45))
+            .unwrap();
+        HeadingModel
+            .apply(&mut state, Command::Forward(10))
+            .unwrap();
+        assert_eq!(state.heading, 315);
+        assert_eq!(state.horizontal_position, 7);
+        assert_eq!(state.lateral_position, -7);
+
+        DirectModel
+            .apply(&mut state, Command::TurnRight(45))
+            .unwrap();
+        assert_eq!(state.heading, 315);
+    }
+
+    #[test]
+    fn test_model_from_name() {
+        assert_eq!(model_from_name("direct").unwrap().name(), "direct");
+        assert_eq!(model_from_name("AIM").unwrap().name(), "aim");
+        assert_eq!(model_from_name("heading").unwrap().name(), "heading");
+        assert!(model_from_name("warp").is_none());
+        assert_eq!(model_constructor("Heading").unwrap()().name(), "heading");
+        assert!(model_constructor("warp").is_none());
+    }
+
+    #[test]
+    fn test_backward_past_origin() {
+        let mut state = SubmarineState::default();
+        DirectModel.apply(&mut state, Command::Backward(4)).unwrap();
+        assert_eq!(state.horizontal_position, -4);
+    }
+
+    #[test]
+    fn test_overflow_leaves_state_unchanged() {
+        let mut state = SubmarineState {
+            horizontal_position: 1,
+            vertical_position: i64::MAX - 1,
+            aim: 1,
+            ..SubmarineState::default()
+        };
+        assert_eq!(
+            DirectModel.apply(&mut state, Command::Down(2)),
+            Err(MovementError::Overflow)
+        );
+        assert_eq!(
+            AimModel.apply(&mut state, Command::Forward(2)),
+            Err(MovementError::Overflow)
+        );
+        assert_eq!(state.horizontal_position, 1);
+        assert_eq!(state.vertical_position, i64::MAX - 1);
+    }
+
+    #[test]
+    fn test_depth_limits() {
+        let limits = DepthLimits {
+            minimum_depth: Some(0),
+            maximum_depth: Some(100),
+        };
+        let mut state = SubmarineState::default();
+        assert_eq!(limits.check(&state), Ok(()));
+
+        state.vertical_position = -1;
+        assert_eq!(
+            limits.check(&state),
+            Err(MovementError::TooShallow {
+                depth: -1,
+                minimum_depth: 0
+            })
+        );
+
+        state.vertical_position = 101;
+        assert_eq!(
+            limits.check(&state).unwrap_err().to_string(),
+            "depth 101 is deeper than the maximum depth 100"
+        );
+        assert_eq!(DepthLimits::default().check(&state), Ok(()));
+    }
+}
@@ -0,0 +1,258 @@
+use crate::{command::Command, movement::SubmarineState};
+use std::{error, fmt};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlanError {
+    TargetBehind {
+        horizontal_position: i64,
+        target_horizontal_position: i64,
+    },
+    Unreachable {
+        depth: i64,
+        target_depth: i64,
+    },
+    Unsupported(String),
+}
+
+impl fmt::Display for PlanError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PlanError::TargetBehind {
+                horizontal_position,
+                target_horizontal_position,
+            } => write!(
+                f,
+                "target horizontal position {} is behind the submarine at {}",
+                target_horizontal_position, horizontal_position
+            ),
+            PlanError::Unreachable {
+                depth,
+                target_depth,
+            } => write!(
+                f,
+                "depth {} cannot be reached from depth {} without moving forward",
+                target_depth, depth
+            ),
+            PlanError::Unsupported(model) => {
+                write!(f, "the {} model does not support planning", model)
+            }
+        }
+    }
+}
+
+impl error::Error for PlanError {}
+
+fn horizontal_distance(
+    start: SubmarineState,
+    target_horizontal_position: i64,
+) -> Result<i128, PlanError> {
+    if target_horizontal_position < start.horizontal_position {
+        return Err(PlanError::TargetBehind {
+            horizontal_position: start.horizontal_position,
+            target_horizontal_position,
+        });
+    }
+    Ok(i128::from(target_horizontal_position) - i128::from(start.horizontal_position))
+}
+
+// Amounts larger than a single command can carry are split into as few
+// commands as possible.
+fn forward_commands(distance: i128) -> impl Iterator<Item = Command> {
+    let limit = i128::from(u32::MAX);
+    let full = distance / limit;
+    let rest = distance % limit;
+    (0..full)
+        .map(|_| Command::Forward(u32::MAX))
+        .chain((rest > 0).then_some(Command::Forward(rest as u32)))
+}
+
+fn vertical_commands(change: i128) -> impl Iterator<Item = Command> {
+    let limit = i128::from(i32::MAX);
+    let command = move |amount: i128| {
+        if change < 0 {
+            Command::Up(amount as i32)
+        } else {
+            Command::Down(amount as i32)
+        }
+    };
+    let full = change.abs() / limit;
+    let rest = change.abs() % limit;
+    (0..full)
+        .map(move |_| command(limit))
+        .chain((rest > 0).then(|| command(rest)))
+}
+
+fn gcd(mut a: i128, mut b: i128) -> i128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a.abs()
+}
+
+pub fn plan_direct(
+    start: SubmarineState,
+    target_horizontal_position: i64,
+    target_depth: i64,
+) -> Result<Vec<Command>, PlanError> {
+    let distance = horizontal_distance(start, target_horizontal_position)?;
+    let change = i128::from(target_depth) - i128::from(start.vertical_position);
+    Ok(forward_commands(distance)
+        .chain(vertical_commands(change))
+        .collect())
+}
+
+// With the aim model depth only changes while moving forward, by the distance
+// travelled times the current aim. Either a single forward at the starting aim
+// lands on the target, or one aim change followed by a single forward does
+// when the remaining depth divides evenly over the distance. Otherwise the
+// course is split into two forward legs whose lengths share the gcd of the
+// distance and the remaining depth, with one aim change between them.
+pub fn plan_aim(
+    start: SubmarineState,
+    target_horizontal_position: i64,
+    target_depth: i64,
+) -> Result<Vec<Command>, PlanError> {
+    let distance = horizontal_distance(start, target_horizontal_position)?;
+    let remaining = i128::from(target_depth)
+        - i128::from(start.vertical_position)
+        - distance * i128::from(start.aim);
+
+    if remaining == 0 {
+        return Ok(forward_commands(distance).collect());
+    }
+    if distance == 0 {
+        return Err(PlanError::Unreachable {
+            depth: start.vertical_position,
+            target_depth,
+        });
+    }
+    if remaining % distance == 0 {
+        return Ok(vertical_commands(remaining / distance)
+            .chain(forward_commands(distance))
+            .collect());
+    }
+
+    let last_leg = gcd(remaining, distance);
+    Ok(forward_commands(distance - last_leg)
+        .chain(vertical_commands(remaining / last_leg))
+        .chain(forward_commands(last_leg))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests_planner {
+    use super::*;
+    use crate::{model_from_name, MovementModel, Submarine};
+
+    fn replay(
+        model: Box<dyn MovementModel>,
+        start: SubmarineState,
+        commands: &[Command],
+    ) -> SubmarineState {
+        let mut submarine = Submarine::from_state(model, start);
+        let instructions = commands.iter().map(Command::to_string).collect();
+        submarine.process_instructions(instructions).unwrap();
+        submarine.state()
+    }
+
+    fn get_targets() -> Vec<(SubmarineState, i64, i64)> {
+        let origin = SubmarineState::default();
+        let cruising = SubmarineState {
+            horizontal_position: -4,
+            vertical_position: 12,
+            aim: 3,
+            ..SubmarineState::default()
+        };
+        vec![
+            (origin, 15, 10),
+            (origin, 15, 60),
+            (origin, 10, 7),
+            (origin, 10, -7),
+            (origin, 0, 0),
+            (cruising, 6, 42),
+            (cruising, 6, 12),
+            (cruising, 9, 1000),
+            (origin, 10_000_000_000, 3),
+        ]
+    }
+
+    #[test]
+    fn test_plans_replay_to_target() {
+        for (start, horizontal_position, depth) in get_targets() {
+            for name in ["direct", "aim"] {
+                let model = model_from_name(name).unwrap();
+                let commands = model.plan(start, horizontal_position, depth).unwrap();
+                let end = replay(model, start, &commands);
+                assert_eq!(
+                    (end.horizontal_position, end.vertical_position),
+                    (horizontal_position, depth),
+                    "{} model from {:?}",
+                    name,
+                    start
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_plan_direct_is_minimal() {
+        let origin = SubmarineState::default();
+        assert_eq!(
+            plan_direct(origin, 15, 10).unwrap(),
+            vec![Command::Forward(15), Command::Down(10)]
+        );
+        assert_eq!(plan_direct(origin, 0, -3).unwrap(), vec![Command::Up(3)]);
+        assert!(plan_direct(origin, 0, 0).unwrap().is_empty());
+        assert_eq!(plan_direct(origin, 10_000_000_000, 0).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_plan_aim_is_minimal() {
+        let origin = SubmarineState::default();
+        assert_eq!(plan_aim(origin, 15, 0).unwrap(), vec![Command::Forward(15)]);
+        assert_eq!(
+            plan_aim(origin, 15, 60).unwrap(),
+            vec![Command::Down(4), Command::Forward(15)]
+        );
+        assert_eq!(
+            plan_aim(origin, 10, 7).unwrap(),
+            vec![Command::Forward(9), Command::Down(7), Command::Forward(1)]
+        );
+        assert_eq!(
+            plan_aim(origin, 10, -4).unwrap(),
+            vec![Command::Forward(8), Command::Up(2), Command::Forward(2)]
+        );
+
+        let aimed = SubmarineState {
+            horizontal_position: 0,
+            vertical_position: 0,
+            aim: 2,
+            ..SubmarineState::default()
+        };
+        assert_eq!(plan_aim(aimed, 5, 10).unwrap(), vec![Command::Forward(5)]);
+    }
+
+    #[test]
+    fn test_plan_errors() {
+        let start = SubmarineState {
+            horizontal_position: 5,
+            vertical_position: 2,
+            ..SubmarineState::default()
+        };
+        assert_eq!(
+            plan_direct(start, 4, 2),
+            Err(PlanError::TargetBehind {
+                horizontal_position: 5,
+                target_horizontal_position: 4
+            })
+        );
+        assert_eq!(
+            plan_aim(start, 5, 3),
+            Err(PlanError::Unreachable {
+                depth: 2,
+                target_depth: 3
+            })
+        );
+        assert_eq!(plan_direct(start, 5, 3).unwrap(), vec![Command::Down(1)]);
+    }
+}
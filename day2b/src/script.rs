@@ -0,0 +1,333 @@
+use crate::command::{is_verb, parse_verb, verb_arity, Command, ParseCommandError};
+use std::{collections::HashMap, error, fmt};
+
+const MAX_COMPILED_COMMANDS: usize = 1_000_000;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScriptErrorKind {
+    Command(ParseCommandError),
+    UnknownMacro(String),
+    DuplicateMacro(String),
+    InvalidMacroName(String),
+    NestedMacro(String),
+    InvalidRepeatCount(String),
+    MissingRepeatCount,
+    ExpectedOpeningBrace,
+    UnclosedBlock,
+    UnexpectedClosingBrace,
+    UnexpectedOpeningBrace,
+    TooManyCommands,
+}
+
+impl fmt::Display for ScriptErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ScriptErrorKind::Command(error) => write!(f, "{}", error),
+            ScriptErrorKind::UnknownMacro(name) => write!(f, "unknown macro {:?}", name),
+            ScriptErrorKind::DuplicateMacro(name) => {
+                write!(f, "macro {:?} is already defined", name)
+            }
+            ScriptErrorKind::InvalidMacroName(name) => write!(f, "invalid macro name {:?}", name),
+            ScriptErrorKind::NestedMacro(name) => {
+                write!(f, "macro {:?} must be defined at the top level", name)
+            }
+            ScriptErrorKind::InvalidRepeatCount(count) => {
+                write!(f, "invalid repeat count {:?}", count)
+            }
+            ScriptErrorKind::MissingRepeatCount => write!(f, "missing repeat count"),
+            ScriptErrorKind::ExpectedOpeningBrace => write!(f, "expected '{{'"),
+            ScriptErrorKind::UnclosedBlock => write!(f, "block is never closed"),
+            ScriptErrorKind::UnexpectedClosingBrace => write!(f, "unexpected '}}'"),
+            ScriptErrorKind::UnexpectedOpeningBrace => write!(f, "unexpected '{{'"),
+            ScriptErrorKind::TooManyCommands => write!(
+                f,
+                "script expands to more than {} commands",
+                MAX_COMPILED_COMMANDS
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScriptError {
+    pub line_number: usize,
+    pub kind: ScriptErrorKind,
+}
+
+impl fmt::Display for ScriptError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line_number, self.kind)
+    }
+}
+
+impl error::Error for ScriptError {}
+
+struct Token<'a> {
+    text: &'a str,
+    line_number: usize,
+}
+
+fn tokenize(source: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    for (index, line) in source.lines().enumerate() {
+        let code = match line.find('#') {
+            Some(comment_start) => &line[..comment_start],
+            None => line,
+        };
+        for word in code.split_whitespace() {
+            let mut rest = word;
+            while !rest.is_empty() {
+                let split_at = match rest.find(['{', '}']) {
+                    Some(0) => 1,
+                    Some(brace) => brace,
+                    None => rest.len(),
+                };
+                tokens.push(Token {
+                    text: &rest[..split_at],
+                    line_number: index + 1,
+                });
+                rest = &rest[split_at..];
+            }
+        }
+    }
+    tokens
+}
+
+struct Compiler<'a> {
+    tokens: Vec<Token<'a>>,
+    position: usize,
+    macros: HashMap<String, Vec<Command>>,
+}
+
+impl<'a> Compiler<'a> {
+    fn next(&mut self) -> Option<&Token<'a>> {
+        let token = self.tokens.get(self.position);
+        if token.is_some() {
+            self.position += 1;
+        }
+        token
+    }
+
+    // Operands must sit on the same line as their keyword, so a statement can
+    // never silently borrow a token from the line below it.
+    fn peek_on_line(&self, line_number: usize) -> Option<&'a str> {
+        self.tokens
+            .get(self.position)
+            .filter(|token| token.line_number == line_number)
+            .map(|token| token.text)
+    }
+
+    fn next_on_line(&mut self, line_number: usize) -> Option<&'a str> {
+        let text = self.peek_on_line(line_number)?;
+        self.position += 1;
+        Some(text)
+    }
+
+    fn expect_opening_brace(&mut self, line_number: usize) -> Result<(), ScriptError> {
+        match self.next() {
+            Some(token) if token.text == "{" => Ok(()),
+            Some(token) => Err(ScriptError {
+                line_number: token.line_number,
+                kind: ScriptErrorKind::ExpectedOpeningBrace,
+            }),
+            None => Err(ScriptError {
+                line_number,
+                kind: ScriptErrorKind::ExpectedOpeningBrace,
+            }),
+        }
+    }
+
+    fn compile_block(&mut self, opened_on: Option<usize>) -> Result<Vec<Command>, ScriptError> {
+        let mut commands: Vec<Command> = Vec::new();
+        loop {
+            let (text, line_number) = match self.next() {
+                Some(token) => (token.text, token.line_number),
+                None => {
+                    return match opened_on {
+                        Some(line_number) => Err(ScriptError {
+                            line_number,
+                            kind: ScriptErrorKind::UnclosedBlock,
+                        }),
+                        None => Ok(commands),
+                    }
+                }
+            };
+            let error = |kind| ScriptError { line_number, kind };
+
+            match text {
+                "}" if opened_on.is_some() => return Ok(commands),
+                "}" => return Err(error(ScriptErrorKind::UnexpectedClosingBrace)),
+                "{" => return Err(error(ScriptErrorKind::UnexpectedOpeningBrace)),
+                "repeat" => {
+                    let count = self
+                        .next_on_line(line_number)
+                        .ok_or_else(|| error(ScriptErrorKind::MissingRepeatCount))?;
+                    let count: usize = count.parse().map_err(|_| {
+                        error(ScriptErrorKind::InvalidRepeatCount(count.to_string()))
+                    })?;
+                    self.expect_opening_brace(line_number)?;
+                    let body = self.compile_block(Some(line_number))?;
+                    if body
+                        .len()
+                        .saturating_mul(count)
+                        .saturating_add(commands.len())
+                        > MAX_COMPILED_COMMANDS
+                    {
+                        return Err(error(ScriptErrorKind::TooManyCommands));
+                    }
+                    for _ in 0..count {
+                        commands.extend_from_slice(&body);
+                    }
+                }
+                "macro" => {
+                    let name = self.next_on_line(line_number).unwrap_or("");
+                    if opened_on.is_some() {
+                        return Err(error(ScriptErrorKind::NestedMacro(name.to_string())));
+                    }
+                    if !is_macro_name(name) {
+                        return Err(error(ScriptErrorKind::InvalidMacroName(name.to_string())));
+                    }
+                    if self.macros.contains_key(name) {
+                        return Err(error(ScriptErrorKind::DuplicateMacro(name.to_string())));
+                    }
+                    self.expect_opening_brace(line_number)?;
+                    let body = self.compile_block(Some(line_number))?;
+                    self.macros.insert(name.to_string(), body);
+                }
+                verb if is_verb(verb) => {
+                    let mut operands = Vec::new();
+                    while operands.len() < verb_arity(verb) {
+                        match self.peek_on_line(line_number) {
+                            Some(operand) if !is_brace(operand) => {
+                                operands.extend(self.next_on_line(line_number))
+                            }
+                            _ => break,
+                        }
+                    }
+                    let command = parse_verb(verb, &operands)
+                        .map_err(|kind| error(ScriptErrorKind::Command(kind)))?;
+                    commands.push(command);
+                }
+                name => {
+                    let body = self.macros.get(name).ok_or_else(|| {
+                        error(if is_macro_name(name) {
+                            ScriptErrorKind::UnknownMacro(name.to_string())
+                        } else {
+                            ScriptErrorKind::Command(ParseCommandError::UnknownVerb(
+                                name.to_string(),
+                            ))
+                        })
+                    })?;
+                    commands.extend_from_slice(body);
+                }
+            }
+
+            if commands.len() > MAX_COMPILED_COMMANDS {
+                return Err(ScriptError {
+                    line_number,
+                    kind: ScriptErrorKind::TooManyCommands,
+                });
+            }
+        }
+    }
+}
+
+fn is_brace(text: &str) -> bool {
+    text == "{" || text == "}"
+}
+
+fn is_macro_name(name: &str) -> bool {
+    let mut characters = name.chars();
+    matches!(characters.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && characters.all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && !is_verb(name)
+        && name != "repeat"
+        && name != "macro"
+}
+
+pub fn compile_script(source: &str) -> Result<Vec<Command>, ScriptError> {
+    let mut compiler = Compiler {
+        tokens: tokenize(source),
+        position: 0,
+        macros: HashMap::new(),
+    };
+    compiler.compile_block(None)
+}
+
+#[cfg(test)]
+mod tests_script {
+    use super::*;
+
+    #[test]
+    fn test_plain_commands() {
+        let commands = compile_script("forward 5\ndown 5\n\nforward 8\n").unwrap();
+        assert_eq!(
+            commands,
+            vec![Command::Forward(5), Command::Down(5), Command::Forward(8)]
+        );
+    }
+
+    #[test]
+    fn test_comments_and_new_verbs() {
+        let source = "# dive plan\nforward 5 # cruise\nhold 3\nbackward 2\nturn left 90\n";
+        assert_eq!(
+            compile_script(source).unwrap(),
+            vec![
+                Command::Forward(5),
+                Command::Hold(3),
+                Command::Backward(2),
+                Command::TurnLeft(90)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_repeat() {
+        let source = "repeat 2 {\n    forward 1\n    repeat 2 { down 3 }\n}\nup 1\n";
+        assert_eq!(
+            compile_script(source).unwrap(),
+            vec![
+                Command::Forward(1),
+                Command::Down(3),
+                Command::Down(3),
+                Command::Forward(1),
+                Command::Down(3),
+                Command::Down(3),
+                Command::Up(1),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_macros() {
+        let source = "macro dive {\n    down 2\n    forward 4\n}\ndive\nrepeat 2 {dive}\n";
+        assert_eq!(compile_script(source).unwrap().len(), 6);
+    }
+
+    #[test]
+    fn test_syntax_errors_report_line_numbers() {
+        let cases = [
+            ("forward 1\nsideways 2\n", 2),
+            ("forward 1\n\nsurface\n", 3),
+            ("repeat 2 {\n forward 1\n", 1),
+            ("forward 1\n}\n", 2),
+            ("repeat many {\n}\n", 1),
+            ("repeat 2\nforward 1\n", 2),
+            ("macro dive {\n macro deeper {\n }\n}\n", 2),
+            ("macro dive {\n}\nmacro dive {\n}\n", 3),
+            ("down\nforward 2\n", 1),
+            ("up -4\n", 1),
+        ];
+        for (source, line_number) in cases.iter() {
+            let error = compile_script(source).unwrap_err();
+            assert_eq!(error.line_number, *line_number, "{}", source);
+        }
+    }
+
+    #[test]
+    fn test_expansion_limit() {
+        let source = "repeat 1000 {\n repeat 1000 {\n  repeat 1000 {\n   hold 1\n  }\n }\n}\n";
+        let error = compile_script(source).unwrap_err();
+        assert_eq!(error.kind, ScriptErrorKind::TooManyCommands);
+    }
+}
@@ -1,54 +1,207 @@
+mod command;
+mod fleet;
+mod movement;
+mod planner;
+mod script;
+mod trajectory;
+
+pub use command::{parse_commands, Command, CommandError, ParseCommandError};
+pub use fleet::{Collision, Fleet, FleetError, FleetReport, SubmarineReport};
+pub use movement::{
+    built_in_models, model_constructor, model_from_name, AimModel, DepthLimits, DirectModel,
+    HeadingModel, ModelConstructor, MovementError, MovementModel, SubmarineState,
+};
+pub use planner::{plan_aim, plan_direct, PlanError};
+pub use script::{compile_script, ScriptError, ScriptErrorKind};
+pub use trajectory::{Trajectory, TrajectoryPoint};
+
+use std::{error, fmt};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SubmarineError {
+    Command(CommandError),
+    Instruction(ParseCommandError),
+    Script(ScriptError),
+    Movement {
+        command_index: usize,
+        command: Command,
+        error: MovementError,
+    },
+}
+
+impl fmt::Display for SubmarineError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SubmarineError::Command(error) => write!(f, "{}", error),
+            SubmarineError::Instruction(error) => write!(f, "{}", error),
+            SubmarineError::Script(error) => write!(f, "{}", error),
+            SubmarineError::Movement {
+                command_index,
+                command,
+                error,
+            } => write!(f, "command {} ({}): {}", command_index + 1, command, error),
+        }
+    }
+}
+
+impl error::Error for SubmarineError {}
+
+impl From<CommandError> for SubmarineError {
+    fn from(error: CommandError) -> Self {
+        SubmarineError::Command(error)
+    }
+}
+
+impl From<ParseCommandError> for SubmarineError {
+    fn from(error: ParseCommandError) -> Self {
+        SubmarineError::Instruction(error)
+    }
+}
+
+impl From<ScriptError> for SubmarineError {
+    fn from(error: ScriptError) -> Self {
+        SubmarineError::Script(error)
+    }
+}
+
 pub struct Submarine {
-    horizontal_position: u32,
-    vertical_position: i32,
-    aim: i32,
+    state: SubmarineState,
+    model: Box<dyn MovementModel>,
+    depth_limits: DepthLimits,
+    trajectory: Option<Trajectory>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModelOutcome {
+    pub model: String,
+    pub state: SubmarineState,
+    pub multiplied_positions: i128,
+    pub error: Option<SubmarineError>,
 }
 
 impl Submarine {
-    pub fn new(horizontal_position: u32, vertical_position: i32, aim: i32) -> Self {
+    pub fn new(horizontal_position: i64, vertical_position: i64, aim: i64) -> Self {
         Submarine {
-            horizontal_position,
-            vertical_position,
-            aim,
+            state: SubmarineState {
+                horizontal_position,
+                vertical_position,
+                aim,
+                ..SubmarineState::default()
+            },
+            model: Box::new(AimModel),
+            depth_limits: DepthLimits::default(),
+            trajectory: None,
         }
     }
 
-    pub fn process_instructions(&mut self, instructions: Vec<String>) {
-        for instruction in instructions {
-            self.process_instruction(instruction)
+    pub fn with_model(model: Box<dyn MovementModel>) -> Self {
+        Submarine::from_state(model, SubmarineState::default())
+    }
+
+    pub fn from_state(model: Box<dyn MovementModel>, state: SubmarineState) -> Self {
+        Submarine {
+            state,
+            model,
+            depth_limits: DepthLimits::default(),
+            trajectory: None,
         }
     }
 
-    fn process_instruction(&mut self, instruction: String) {
-        let split = instruction.split(' ');
-        let split_vec = split.collect::<Vec<&str>>();
-        let command = split_vec[0].to_lowercase();
-        let count = split_vec[1];
+    pub fn state(&self) -> SubmarineState {
+        self.state
+    }
 
-        match command.as_ref() {
-            "forward" => self.forward(count.parse::<u32>().unwrap()),
-            "up" => self.up(count.parse::<i32>().unwrap()),
-            "down" => self.down(count.parse::<i32>().unwrap()),
-            _ => println!("Invalid command"),
-        }
+    pub fn model_name(&self) -> &str {
+        self.model.name()
+    }
+
+    pub fn set_depth_limits(&mut self, depth_limits: DepthLimits) {
+        self.depth_limits = depth_limits;
+    }
+
+    pub fn depth_limits(&self) -> DepthLimits {
+        self.depth_limits
+    }
+
+    pub fn plan_course(
+        &self,
+        target_horizontal_position: i64,
+        target_depth: i64,
+    ) -> Result<Vec<Command>, PlanError> {
+        self.model
+            .plan(self.state, target_horizontal_position, target_depth)
+    }
+
+    pub fn record_trajectory(&mut self) {
+        self.trajectory = Some(Trajectory::new(self.state));
+    }
+
+    pub fn trajectory(&self) -> Option<&Trajectory> {
+        self.trajectory.as_ref()
     }
 
-    fn forward(&mut self, count: u32) {
-        self.horizontal_position += count;
-        self.vertical_position += count as i32 * self.aim;
+    pub fn process_instructions(
+        &mut self,
+        instructions: Vec<String>,
+    ) -> Result<(), SubmarineError> {
+        self.apply_all(&parse_commands(&instructions)?)
     }
 
-    fn down(&mut self, count: i32) {
-        self.aim += count;
+    pub fn process_instruction(&mut self, instruction: &str) -> Result<(), SubmarineError> {
+        let command = instruction.parse::<Command>()?;
+        self.apply_all(&[command])
     }
 
-    fn up(&mut self, count: i32) {
-        self.aim -= count;
+    pub fn run_script(&mut self, source: &str) -> Result<(), SubmarineError> {
+        self.apply_all(&compile_script(source)?)
     }
 
-    pub fn multiply_positions(&self) -> i32 {
-        (self.horizontal_position as i32 * self.vertical_position).abs()
+    pub fn apply_all(&mut self, commands: &[Command]) -> Result<(), SubmarineError> {
+        for (command_index, command) in commands.iter().enumerate() {
+            self.apply(*command)
+                .map_err(|error| SubmarineError::Movement {
+                    command_index,
+                    command: *command,
+                    error,
+                })?;
+        }
+        Ok(())
     }
+
+    // A rejected command leaves the submarine where it was and is not recorded
+    // in the trajectory.
+    pub fn apply(&mut self, command: Command) -> Result<(), MovementError> {
+        let mut next = self.state;
+        self.model.apply(&mut next, command)?;
+        self.depth_limits.check(&next)?;
+        self.state = next;
+        if let Some(trajectory) = self.trajectory.as_mut() {
+            trajectory.record(command, self.state);
+        }
+        Ok(())
+    }
+
+    // Signed, so a submarine behind its start or above the surface shows up
+    // as a negative product.
+    pub fn multiply_positions(&self) -> i128 {
+        i128::from(self.state.horizontal_position) * i128::from(self.state.vertical_position)
+    }
+}
+
+pub fn compare_models(commands: &[Command]) -> Vec<ModelOutcome> {
+    built_in_models()
+        .into_iter()
+        .map(|model| {
+            let mut submarine = Submarine::with_model(model);
+            let error = submarine.apply_all(commands).err();
+            ModelOutcome {
+                model: submarine.model_name().to_string(),
+                state: submarine.state(),
+                multiplied_positions: submarine.multiply_positions(),
+                error,
+            }
+        })
+        .collect()
 }
 
 #[cfg(test)]
@@ -58,64 +211,71 @@ mod tests_day2a {
     #[test]
     fn test_create_submarine() {
         let submarine = Submarine::new(0, 0, 0);
-        assert_eq!(submarine.horizontal_position, 0);
-        assert_eq!(submarine.vertical_position, 0);
-        assert_eq!(submarine.aim, 0);
+        assert_eq!(submarine.state.horizontal_position, 0);
+        assert_eq!(submarine.state.vertical_position, 0);
+        assert_eq!(submarine.state.aim, 0);
     }
 
     #[test]
     fn test_move_submarine_forward() {
         let mut submarine = Submarine::new(0, 0, 2);
 
-        submarine.forward(5);
-        assert_eq!(submarine.horizontal_position, 5);
-        assert_eq!(submarine.aim, 2);
-        assert_eq!(submarine.vertical_position, 10);
+        submarine.apply(Command::Forward(5)).unwrap();
+        assert_eq!(submarine.state.horizontal_position, 5);
+        assert_eq!(submarine.state.aim, 2);
+        assert_eq!(submarine.state.vertical_position, 10);
 
-        submarine.forward(2);
-        assert_eq!(submarine.horizontal_position, 7);
-        assert_eq!(submarine.aim, 2);
-        assert_eq!(submarine.vertical_position, 14)
+        submarine.apply(Command::Forward(2)).unwrap();
+        assert_eq!(submarine.state.horizontal_position, 7);
+        assert_eq!(submarine.state.aim, 2);
+        assert_eq!(submarine.state.vertical_position, 14)
     }
 
     #[test]
     fn test_move_submarine_down() {
         let mut submarine = Submarine::new(0, 0, 0);
 
-        submarine.down(3);
-        assert_eq!(submarine.vertical_position, 0);
-        assert_eq!(submarine.aim, 3);
+        submarine.apply(Command::Down(3)).unwrap();
+        assert_eq!(submarine.state.vertical_position, 0);
+        assert_eq!(submarine.state.aim, 3);
 
-        submarine.down(2);
-        assert_eq!(submarine.vertical_position, 0);
-        assert_eq!(submarine.aim, 5);
+        submarine.apply(Command::Down(2)).unwrap();
+        assert_eq!(submarine.state.vertical_position, 0);
+        assert_eq!(submarine.state.aim, 5);
     }
 
     #[test]
     fn test_move_submarine_up() {
         let mut submarine = Submarine::new(0, 0, 0);
 
-        submarine.up(8);
-        assert_eq!(submarine.vertical_position, 0);
-        assert_eq!(submarine.aim, -8);
+        submarine.apply(Command::Up(8)).unwrap();
+        assert_eq!(submarine.state.vertical_position, 0);
+        assert_eq!(submarine.state.aim, -8);
 
-        submarine.up(10);
-        assert_eq!(submarine.vertical_position, 0);
-        assert_eq!(submarine.aim, -18);
+        submarine.apply(Command::Up(10)).unwrap();
+        assert_eq!(submarine.state.vertical_position, 0);
+        assert_eq!(submarine.state.aim, -18);
     }
 
     #[test]
     fn test_process_instruction() {
         let mut submarine = Submarine::new(0, 0, 0);
 
-        submarine.process_instruction("forward 5".to_string());
-        assert_eq!(submarine.horizontal_position, 5);
+        submarine.process_instruction("forward 5").unwrap();
+        assert_eq!(submarine.state.horizontal_position, 5);
 
-        submarine.process_instruction("up 5".to_string());
-        assert_eq!(submarine.aim, -5);
+        submarine.process_instruction("up 5").unwrap();
+        assert_eq!(submarine.state.aim, -5);
 
-        submarine.process_instruction("down 10".to_string());
-        assert_eq!(submarine.aim, 5);
+        submarine.process_instruction("down 10").unwrap();
+        assert_eq!(submarine.state.aim, 5);
+
+        let error = submarine.process_instruction("dive 2").unwrap_err();
+        assert_eq!(
+            error,
+            SubmarineError::Instruction(ParseCommandError::UnknownVerb("dive".to_string()))
+        );
+        assert_eq!(error.to_string(), "unknown command \"dive\"");
     }
 
     #[test]
@@ -124,7 +284,7 @@ mod tests_day2a {
         assert_eq!(submarine.multiply_positions(), 50);
 
         let submarine2 = Submarine::new(5, -20, 0);
-        assert_eq!(submarine2.multiply_positions(), 100);
+        assert_eq!(submarine2.multiply_positions(), -100);
     }
 
     #[test]
@@ -138,9 +298,212 @@ mod tests_day2a {
             "forward 2".to_string(),
         ];
         let mut submarine = Submarine::new(0, 0, 0);
-        submarine.process_instructions(instructions);
-        assert_eq!(submarine.horizontal_position, 15);
-        assert_eq!(submarine.vertical_position, 60);
+        submarine.process_instructions(instructions).unwrap();
+        assert_eq!(submarine.state.horizontal_position, 15);
+        assert_eq!(submarine.state.vertical_position, 60);
+        assert_eq!(submarine.multiply_positions(), 900);
+    }
+
+    #[test]
+    fn test_apply_commands() {
+        let mut submarine = Submarine::new(0, 0, 0);
+        submarine.apply(Command::Down(5)).unwrap();
+        submarine.apply(Command::Forward(8)).unwrap();
+        assert_eq!(submarine.state.horizontal_position, 8);
+        assert_eq!(submarine.state.vertical_position, 40);
+    }
+
+    #[test]
+    fn test_invalid_instructions_are_rejected() {
+        let instructions = vec!["forward 5".to_string(), "sideways 2".to_string()];
+        let mut submarine = Submarine::new(0, 0, 0);
+        let error = submarine.process_instructions(instructions).unwrap_err();
+        match error {
+            SubmarineError::Command(error) => assert_eq!(error.line_number, 2),
+            _ => panic!("expected a command error"),
+        }
+        assert_eq!(submarine.state.horizontal_position, 0);
+    }
+
+    #[test]
+    fn test_direct_model_sample_data() {
+        let instructions = vec![
+            "forward 5".to_string(),
+            "down 5".to_string(),
+            "forward 8".to_string(),
+            "up 3".to_string(),
+            "down 8".to_string(),
+            "forward 2".to_string(),
+        ];
+        let mut submarine = Submarine::with_model(model_from_name("direct").unwrap());
+        submarine.process_instructions(instructions).unwrap();
+        assert_eq!(submarine.state().vertical_position, 10);
+        assert_eq!(submarine.multiply_positions(), 150);
+    }
+
+    #[test]
+    fn test_compare_models() {
+        let instructions = vec![
+            "forward 5",
+            "down 5",
+            "forward 8",
+            "up 3",
+            "down 8",
+            "forward 2",
+        ];
+        let outcomes = compare_models(&parse_commands(&instructions).unwrap());
+        assert_eq!(outcomes.len(), 3);
+        assert_eq!(outcomes[0].model, "direct");
+        assert_eq!(outcomes[0].multiplied_positions, 150);
+        assert_eq!(outcomes[1].model, "aim");
+        assert_eq!(outcomes[1].multiplied_positions, 900);
+        assert_eq!(outcomes[2].model, "heading");
+        assert_eq!(outcomes[2].multiplied_positions, 900);
+    }
+
+    #[test]
+    fn test_record_trajectory() {
+        let instructions = vec![
+            "forward 5".to_string(),
+            "down 5".to_string(),
+            "forward 8".to_string(),
+            "up 3".to_string(),
+            "down 8".to_string(),
+            "forward 2".to_string(),
+        ];
+        let mut submarine = Submarine::new(0, 0, 0);
+        assert!(submarine.trajectory().is_none());
+
+        submarine.record_trajectory();
+        submarine.process_instructions(instructions).unwrap();
+        let trajectory = submarine.trajectory().unwrap();
+        assert_eq!(trajectory.points().len(), 6);
+        assert_eq!(trajectory.points()[5].state, submarine.state());
+        assert_eq!(trajectory.max_depth(), 60);
+        assert_eq!(trajectory.first_command_deeper_than(0), Some(2));
+    }
+
+    #[test]
+    fn test_run_script() {
+        let source = "# sample course as a script\n\
+                      macro dive {\n    down 5\n    forward 8\n}\n\
+                      forward 5\ndive\nup 3\ndown 8\nforward 2\n";
+        let mut submarine = Submarine::new(0, 0, 0);
+        submarine.run_script(source).unwrap();
         assert_eq!(submarine.multiply_positions(), 900);
+
+        let error = submarine.run_script("forward 1\nrepeat 2 {\n").unwrap_err();
+        match error {
+            SubmarineError::Script(error) => assert_eq!(error.line_number, 2),
+            _ => panic!("expected a script error"),
+        }
+    }
+
+    #[test]
+    fn test_move_backward_past_start() {
+        let mut submarine = Submarine::with_model(Box::new(DirectModel));
+        submarine.process_instruction("backward 7").unwrap();
+        submarine.process_instruction("down 3").unwrap();
+        assert_eq!(submarine.state().horizontal_position, -7);
+        assert_eq!(submarine.multiply_positions(), -21);
+    }
+
+    #[test]
+    fn test_large_positions_do_not_wrap() {
+        let mut submarine = Submarine::new(0, 0, 0);
+        submarine.apply(Command::Down(1_000_000)).unwrap();
+        submarine.apply(Command::Forward(4_000_000)).unwrap();
+        assert_eq!(submarine.state().vertical_position, 4_000_000_000_000);
+        assert_eq!(submarine.multiply_positions(), 16_000_000_000_000_000_000);
+    }
+
+    #[test]
+    fn test_overflow_is_reported() {
+        let mut submarine = Submarine::new(0, i64::MAX - 10, 20);
+        submarine.record_trajectory();
+        let instructions = vec!["forward 1".to_string(), "forward 1".to_string()];
+        let error = submarine.process_instructions(instructions).unwrap_err();
+        assert_eq!(
+            error,
+            SubmarineError::Movement {
+                command_index: 0,
+                command: Command::Forward(1),
+                error: MovementError::Overflow,
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            "command 1 (forward 1): position overflowed"
+        );
+        assert_eq!(submarine.state().vertical_position, i64::MAX - 10);
+        assert!(submarine.trajectory().unwrap().points().is_empty());
+    }
+
+    #[test]
+    fn test_surfacing_is_rejected() {
+        let mut submarine = Submarine::with_model(Box::new(DirectModel));
+        submarine.set_depth_limits(DepthLimits::surface_only());
+        submarine.process_instruction("down 2").unwrap();
+        let error = submarine.process_instruction("up 3").unwrap_err();
+        assert!(matches!(
+            error,
+            SubmarineError::Movement {
+                error: MovementError::TooShallow {
+                    depth: -1,
+                    minimum_depth: 0
+                },
+                ..
+            }
+        ));
+        assert_eq!(submarine.state().vertical_position, 2);
+    }
+
+    #[test]
+    fn test_heading_model_course() {
+        let source = "down 1\nforward 4\nturn right 90\nforward 3\n";
+        let mut submarine = Submarine::with_model(Box::new(HeadingModel));
+        submarine.run_script(source).unwrap();
+        let state = submarine.state();
+        assert_eq!(
+            (
+                state.horizontal_position,
+                state.lateral_position,
+                state.vertical_position
+            ),
+            (4, 3, 7)
+        );
+        assert_eq!(state.manhattan_distance(), 14);
+        assert_eq!(state.euclidean_distance(), 74.0f64.sqrt());
+        assert!(submarine.plan_course(10, 10).is_err());
+    }
+
+    #[test]
+    fn test_plan_course() {
+        let mut submarine = Submarine::new(0, 0, 0);
+        let commands = submarine.plan_course(15, 60).unwrap();
+        assert_eq!(commands, vec![Command::Down(4), Command::Forward(15)]);
+
+        let instructions = commands.iter().map(Command::to_string).collect();
+        submarine.process_instructions(instructions).unwrap();
+        assert_eq!(submarine.multiply_positions(), 900);
+        assert!(submarine.plan_course(10, 60).is_err());
+    }
+
+    #[test]
+    fn test_compare_models_reports_errors() {
+        let commands = [
+            Command::Down(i32::MAX),
+            Command::Forward(u32::MAX),
+            Command::Forward(u32::MAX),
+        ];
+        let outcomes = compare_models(&commands);
+        assert!(outcomes[0].error.is_none());
+        assert!(matches!(
+            outcomes[1].error,
+            Some(SubmarineError::Movement {
+                command_index: 2,
+                ..
+            })
+        ));
     }
 }
@@ -0,0 +1,154 @@
+use crate::{command::Command, movement::SubmarineState};
+use std::io::{prelude::*, Error};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TrajectoryPoint {
+    pub command_index: usize,
+    pub command: Command,
+    pub state: SubmarineState,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Trajectory {
+    start: SubmarineState,
+    points: Vec<TrajectoryPoint>,
+}
+
+impl Trajectory {
+    pub fn new(start: SubmarineState) -> Self {
+        Trajectory {
+            start,
+            points: Vec::new(),
+        }
+    }
+
+    pub fn record(&mut self, command: Command, state: SubmarineState) {
+        self.points.push(TrajectoryPoint {
+            command_index: self.points.len(),
+            command,
+            state,
+        });
+    }
+
+    pub fn start(&self) -> SubmarineState {
+        self.start
+    }
+
+    pub fn points(&self) -> &[TrajectoryPoint] {
+        &self.points
+    }
+
+    pub fn max_depth(&self) -> i64 {
+        self.points
+            .iter()
+            .map(|point| point.state.vertical_position)
+            .fold(self.start.vertical_position, i64::max)
+    }
+
+    pub fn first_command_deeper_than(&self, depth: i64) -> Option<usize> {
+        self.points
+            .iter()
+            .find(|point| point.state.vertical_position > depth)
+            .map(|point| point.command_index)
+    }
+
+    pub fn total_distance(&self) -> f64 {
+        let mut previous = self.start;
+        let mut distance = 0.0;
+        for point in &self.points {
+            let horizontal =
+                point.state.horizontal_position as f64 - previous.horizontal_position as f64;
+            let lateral = point.state.lateral_position as f64 - previous.lateral_position as f64;
+            let vertical = point.state.vertical_position as f64 - previous.vertical_position as f64;
+            distance += horizontal.hypot(lateral).hypot(vertical);
+            previous = point.state;
+        }
+        distance
+    }
+
+    pub fn write_csv(&self, mut writer: impl Write) -> Result<(), Error> {
+        writeln!(
+            writer,
+            "command_index,command,horizontal_position,vertical_position,aim,lateral_position,heading"
+        )?;
+        for point in &self.points {
+            writeln!(
+                writer,
+                "{},{},{},{},{},{},{}",
+                point.command_index,
+                point.command,
+                point.state.horizontal_position,
+                point.state.vertical_position,
+                point.state.aim,
+                point.state.lateral_position,
+                point.state.heading
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests_trajectory {
+    use super::*;
+
+    fn get_sample_trajectory() -> Trajectory {
+        let mut trajectory = Trajectory::new(SubmarineState::default());
+        let states = [(5, 0, 0), (5, 0, 5), (13, 40, 5), (13, 40, 2)];
+        let commands = [
+            Command::Forward(5),
+            Command::Down(5),
+            Command::Forward(8),
+            Command::Up(3),
+        ];
+        for (command, (horizontal_position, vertical_position, aim)) in
+            commands.iter().zip(states.iter())
+        {
+            trajectory.record(
+                *command,
+                SubmarineState {
+                    horizontal_position: *horizontal_position,
+                    vertical_position: *vertical_position,
+                    aim: *aim,
+                    ..SubmarineState::default()
+                },
+            );
+        }
+        trajectory
+    }
+
+    #[test]
+    fn test_trajectory_queries() {
+        let trajectory = get_sample_trajectory();
+        assert_eq!(trajectory.points().len(), 4);
+        assert_eq!(trajectory.max_depth(), 40);
+        assert_eq!(trajectory.first_command_deeper_than(10), Some(2));
+        assert_eq!(trajectory.first_command_deeper_than(40), None);
+        assert_eq!(trajectory.total_distance(), 5.0 + 8.0f64.hypot(40.0));
+    }
+
+    #[test]
+    fn test_empty_trajectory() {
+        let trajectory = Trajectory::new(SubmarineState {
+            horizontal_position: 0,
+            vertical_position: 7,
+            ..SubmarineState::default()
+        });
+        assert_eq!(trajectory.max_depth(), 7);
+        assert_eq!(trajectory.total_distance(), 0.0);
+    }
+
+    #[test]
+    fn test_write_csv() {
+        let mut csv: Vec<u8> = Vec::new();
+        get_sample_trajectory().write_csv(&mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(
+            lines[0],
+            "command_index,command,horizontal_position,vertical_position,aim,lateral_position,heading"
+        );
+        assert_eq!(lines[3], "2,forward 8,13,40,5,0,0");
+        assert_eq!(lines.len(), 5);
+    }
+}
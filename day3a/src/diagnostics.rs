@@ -80,7 +80,7 @@ mod tests_day3a {
     use super::*;
 
     fn get_sample_data() -> Vec<String> {
-        return vec![
+        vec![
             "00100".to_owned(),
             "11110".to_owned(),
             "10110".to_owned(),
@@ -93,7 +93,7 @@ mod tests_day3a {
             "11001".to_owned(),
             "00010".to_owned(),
             "01010".to_owned(),
-        ];
+        ]
     }
 
     #[test]
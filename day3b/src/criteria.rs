@@ -0,0 +1,254 @@
+use crate::{
+    parallel::{retain_in_parallel, sum_counts},
+    readings::Readings,
+};
+use std::{error, fmt};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Commonality {
+    MostCommon,
+    LeastCommon,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TieBreak {
+    Zero,
+    One,
+    Error,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CriteriaError {
+    NoReadings,
+    Tie { position: usize },
+}
+
+impl fmt::Display for CriteriaError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CriteriaError::NoReadings => write!(f, "there are no readings to select from"),
+            CriteriaError::Tie { position } => {
+                write!(f, "ones and zeros are tied at position {}", position)
+            }
+        }
+    }
+}
+
+impl error::Error for CriteriaError {}
+
+pub trait BitCriterion {
+    // Returns whether readings with a one at `position` are kept.
+    fn select(
+        &self,
+        position: usize,
+        count_of_one: usize,
+        count_of_zero: usize,
+    ) -> Result<bool, CriteriaError>;
+}
+
+// Any `Fn(count_of_one, count_of_zero) -> bool` is a criterion that cannot fail.
+impl<F: Fn(usize, usize) -> bool> BitCriterion for F {
+    fn select(
+        &self,
+        _position: usize,
+        count_of_one: usize,
+        count_of_zero: usize,
+    ) -> Result<bool, CriteriaError> {
+        Ok(self(count_of_one, count_of_zero))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BitCriteria {
+    pub commonality: Commonality,
+    pub tie_break: TieBreak,
+}
+
+impl BitCriteria {
+    pub const fn new(commonality: Commonality, tie_break: TieBreak) -> Self {
+        BitCriteria {
+            commonality,
+            tie_break,
+        }
+    }
+
+    pub const fn gamma() -> Self {
+        BitCriteria::new(Commonality::MostCommon, TieBreak::Zero)
+    }
+
+    pub const fn epsilon() -> Self {
+        BitCriteria::new(Commonality::LeastCommon, TieBreak::Zero)
+    }
+
+    pub const fn oxygen_generator() -> Self {
+        BitCriteria::new(Commonality::MostCommon, TieBreak::One)
+    }
+
+    pub const fn co2_scrubber() -> Self {
+        BitCriteria::new(Commonality::LeastCommon, TieBreak::Zero)
+    }
+}
+
+impl BitCriterion for BitCriteria {
+    fn select(
+        &self,
+        position: usize,
+        count_of_one: usize,
+        count_of_zero: usize,
+    ) -> Result<bool, CriteriaError> {
+        if count_of_one == count_of_zero {
+            return match self.tie_break {
+                TieBreak::Zero => Ok(false),
+                TieBreak::One => Ok(true),
+                TieBreak::Error => Err(CriteriaError::Tie { position }),
+            };
+        }
+        Ok(match self.commonality {
+            Commonality::MostCommon => count_of_one > count_of_zero,
+            Commonality::LeastCommon => count_of_one < count_of_zero,
+        })
+    }
+}
+
+pub fn select_bits(
+    ones_per_position: &[usize],
+    total: usize,
+    criterion: &impl BitCriterion,
+) -> Result<Vec<bool>, CriteriaError> {
+    ones_per_position
+        .iter()
+        .enumerate()
+        .map(|(position, &count_of_one)| {
+            criterion.select(position, count_of_one, total - count_of_one)
+        })
+        .collect()
+}
+
+// Narrows the readings one position at a time, keeping those whose bit the
+// criterion selects, until a single reading is left. A position where every
+// remaining reading has the same bit narrows nothing, so it is skipped rather
+// than letting a least-common criterion discard everything. Returns the
+// index of the surviving reading; among identical survivors the first wins.
+pub fn filter_readings(
+    readings: &Readings,
+    criterion: &impl BitCriterion,
+) -> Result<usize, CriteriaError> {
+    filter_readings_in_parallel(readings, criterion, 1)
+}
+
+// Counts the ones among the remaining readings, and narrows them, on up to
+// `threads` threads at every position; the criterion itself only runs on the
+// calling thread.
+pub fn filter_readings_in_parallel(
+    readings: &Readings,
+    criterion: &impl BitCriterion,
+    threads: usize,
+) -> Result<usize, CriteriaError> {
+    let mut values_to_process: Vec<usize> = (0..readings.len()).collect();
+    if values_to_process.is_empty() {
+        return Err(CriteriaError::NoReadings);
+    }
+
+    for position in 0..readings.width() {
+        if values_to_process.len() == 1 {
+            break;
+        }
+        let remaining = &values_to_process;
+        let count_of_one = sum_counts(remaining.len(), threads, |range| {
+            let ones = remaining[range]
+                .iter()
+                .filter(|&&index| readings.bit(index, position))
+                .count();
+            vec![ones]
+        })[0];
+        let count_of_zero = values_to_process.len() - count_of_one;
+        if count_of_one == 0 || count_of_zero == 0 {
+            continue;
+        }
+        let keep = criterion.select(position, count_of_one, count_of_zero)?;
+        retain_in_parallel(&mut values_to_process, threads, |&index| {
+            readings.bit(index, position) == keep
+        });
+    }
+
+    Ok(values_to_process[0])
+}
+
+#[cfg(test)]
+mod tests_criteria {
+    use super::*;
+
+    #[test]
+    fn test_bit_criteria() {
+        let most_common = BitCriteria::new(Commonality::MostCommon, TieBreak::Error);
+        assert_eq!(most_common.select(0, 3, 2), Ok(true));
+        assert_eq!(most_common.select(0, 2, 3), Ok(false));
+        assert_eq!(
+            most_common.select(4, 2, 2),
+            Err(CriteriaError::Tie { position: 4 })
+        );
+
+        assert_eq!(BitCriteria::oxygen_generator().select(0, 2, 2), Ok(true));
+        assert_eq!(BitCriteria::co2_scrubber().select(0, 2, 2), Ok(false));
+        assert_eq!(BitCriteria::co2_scrubber().select(0, 1, 3), Ok(true));
+        assert_eq!(BitCriteria::gamma().select(0, 2, 2), Ok(false));
+    }
+
+    #[test]
+    fn test_select_bits() {
+        assert_eq!(
+            select_bits(&[3, 1, 2], 4, &BitCriteria::gamma()),
+            Ok(vec![true, false, false])
+        );
+        assert_eq!(
+            select_bits(
+                &[3, 1, 2],
+                4,
+                &BitCriteria::new(Commonality::LeastCommon, TieBreak::Error)
+            ),
+            Err(CriteriaError::Tie { position: 2 })
+        );
+    }
+
+    #[test]
+    fn test_filter_readings() {
+        let readings = Readings::from_strings(&["101", "100", "011", "011"]);
+        assert_eq!(
+            filter_readings(&readings, &BitCriteria::oxygen_generator()),
+            Ok(0)
+        );
+        assert_eq!(
+            filter_readings(&readings, &BitCriteria::co2_scrubber()),
+            Ok(2)
+        );
+
+        let always_ones = |_: usize, _: usize| true;
+        assert_eq!(
+            filter_readings(&Readings::from_strings(&["00", "01"]), &always_ones),
+            Ok(1)
+        );
+        assert_eq!(
+            filter_readings(
+                &readings,
+                &BitCriteria::new(Commonality::MostCommon, TieBreak::Error)
+            ),
+            Err(CriteriaError::Tie { position: 0 })
+        );
+        assert_eq!(
+            filter_readings(&Readings::new(3), &always_ones),
+            Err(CriteriaError::NoReadings)
+        );
+    }
+
+    #[test]
+    fn test_filter_readings_in_parallel() {
+        let values: Vec<u64> = (0..50_000u64).map(|value| value * 7919 % 65_536).collect();
+        let readings = Readings::from_values(&values, 16);
+        for criteria in [BitCriteria::oxygen_generator(), BitCriteria::co2_scrubber()] {
+            assert_eq!(
+                filter_readings_in_parallel(&readings, &criteria, 4),
+                filter_readings(&readings, &criteria)
+            );
+        }
+    }
+}
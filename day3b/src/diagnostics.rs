@@ -1,11 +1,64 @@
+mod criteria;
+mod parallel;
+mod radix;
+mod readings;
+mod report;
+mod results;
+mod trie;
+
+pub use criteria::{
+    filter_readings, filter_readings_in_parallel, select_bits, BitCriteria, BitCriterion,
+    Commonality, CriteriaError, TieBreak,
+};
+pub use radix::{filter_radix_readings, select_digit, RadixReadings};
+pub use readings::{bits_to_value, ReadingError, ReadingErrorKind, Readings};
+pub use report::{BitStatistics, DiagnosticReport};
+pub use results::{DiagnosticError, DiagnosticResult, Diagnostics};
+pub use trie::ReadingTrie;
+
+use parallel::{available_threads, PARALLEL_THRESHOLD};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RatingStrategy {
+    // The trie for small inputs; for large ones the packed filter, which needs
+    // no memory beyond the readings and runs on every available core.
+    #[default]
+    Auto,
+    Trie,
+    Filter,
+}
+
+impl RatingStrategy {
+    fn for_readings(self, readings: &Readings) -> RatingStrategy {
+        match self {
+            RatingStrategy::Auto if readings.len() < PARALLEL_THRESHOLD => RatingStrategy::Trie,
+            RatingStrategy::Auto => RatingStrategy::Filter,
+            strategy => strategy,
+        }
+    }
+
+    pub fn select(
+        &self,
+        readings: &Readings,
+        criterion: &impl BitCriterion,
+    ) -> Result<usize, CriteriaError> {
+        match self.for_readings(readings) {
+            RatingStrategy::Trie => ReadingTrie::new(readings).select(criterion),
+            _ => filter_readings_in_parallel(readings, criterion, available_threads()),
+        }
+    }
+}
+
 pub struct PowerDiagnostic {
-    gamma_rate: isize,
-    epsilon_rate: isize,
-    pub power_consumption: isize,
+    gamma_rate: u64,
+    epsilon_rate: u64,
+    pub power_consumption: u128,
 
-    oxygen_generator_rating: isize,
-    co2_scrubber_rating: isize,
-    pub life_support_rating: isize,
+    oxygen_generator_rating: u64,
+    co2_scrubber_rating: u64,
+    pub life_support_rating: u128,
+
+    rating_strategy: RatingStrategy,
 }
 
 impl Default for PowerDiagnostic {
@@ -23,151 +76,153 @@ impl PowerDiagnostic {
             oxygen_generator_rating: 0,
             co2_scrubber_rating: 0,
             life_support_rating: 0,
+            rating_strategy: RatingStrategy::default(),
         }
     }
 
-    pub fn calculate_gamma_rate(&mut self, binary_values: &[String]) {
-        let mut gamma_string: String = "".to_owned();
-
-        for i in 0..binary_values[0].len() {
-            let mut count_of_one = 0;
-            let mut count_of_zero = 0;
-
-            for binary_value in binary_values {
-                if binary_value.chars().nth(i).unwrap() == '1' {
-                    count_of_one += 1;
-                } else {
-                    count_of_zero += 1;
-                }
-            }
-
-            if count_of_one > count_of_zero {
-                gamma_string.push('1');
-            } else {
-                gamma_string.push('0');
-            }
+    pub fn with_rating_strategy(rating_strategy: RatingStrategy) -> Self {
+        PowerDiagnostic {
+            rating_strategy,
+            ..Self::new()
         }
-
-        self.gamma_rate = isize::from_str_radix(&gamma_string[..], 2).unwrap();
-        self.calculate_power_consumption();
     }
 
-    pub fn calculate_epsilon_rate(&mut self, binary_values: &[String]) {
-        let mut epsilon_string: String = "".to_owned();
-
-        for i in 0..binary_values[0].len() {
-            let mut count_of_one = 0;
-            let mut count_of_zero = 0;
+    pub fn report<S: AsRef<str>>(&self, binary_values: &[S]) -> DiagnosticReport {
+        Diagnostics::with_rating_strategy(self.rating_strategy).report(binary_values)
+    }
 
-            for binary_value in binary_values {
-                if binary_value.chars().nth(i).unwrap() == '1' {
-                    count_of_one += 1;
-                } else {
-                    count_of_zero += 1;
-                }
-            }
+    // A failed calculation leaves the previous rates and ratings untouched.
+    pub fn calculate_gamma_rate(
+        &mut self,
+        binary_values: &[String],
+    ) -> Result<(), DiagnosticError> {
+        let readings = Readings::parse(binary_values)?;
+        self.gamma_rate = rate(
+            &readings,
+            &readings.ones_per_position(),
+            BitCriteria::gamma(),
+        )?;
+        self.calculate_power_consumption();
+        Ok(())
+    }
 
-            if count_of_one < count_of_zero {
-                epsilon_string.push('1');
-            } else {
-                epsilon_string.push('0');
-            }
-        }
+    pub fn calculate_epsilon_rate(
+        &mut self,
+        binary_values: &[String],
+    ) -> Result<(), DiagnosticError> {
+        let readings = Readings::parse(binary_values)?;
+        self.epsilon_rate = rate(
+            &readings,
+            &readings.ones_per_position(),
+            BitCriteria::epsilon(),
+        )?;
+        self.calculate_power_consumption();
+        Ok(())
+    }
 
-        self.epsilon_rate = isize::from_str_radix(&epsilon_string[..], 2).unwrap();
+    pub fn calculate_power_rates(&mut self, readings: &Readings) -> Result<(), DiagnosticError> {
+        let ones_per_position = readings.ones_per_position();
+        let gamma_rate = rate(readings, &ones_per_position, BitCriteria::gamma())?;
+        self.epsilon_rate = rate(readings, &ones_per_position, BitCriteria::epsilon())?;
+        self.gamma_rate = gamma_rate;
         self.calculate_power_consumption();
+        Ok(())
     }
 
     fn calculate_power_consumption(&mut self) {
-        self.power_consumption = self.gamma_rate * self.epsilon_rate;
-    }
-
-    pub fn calculate_oxygen_generator_rating(&mut self, binary_values: &[String]) {
-        let mut oxygen_generator_rating_string: String = "".to_owned();
-        let mut values_to_process: Vec<String> = binary_values.to_owned();
-
-        for i in 0..binary_values[0].len() {
-            let mut count_of_one = 0;
-            let mut count_of_zero = 0;
-            for value in &values_to_process {
-                let processing_character = value.chars().nth(i).unwrap();
-                if processing_character == '1' {
-                    count_of_one += 1;
-                } else {
-                    count_of_zero += 1;
-                }
-            }
-
-            let keep_values_starting_with_one: bool = count_of_one >= count_of_zero;
-            let keep_values_starting_with_zero: bool = count_of_one < count_of_zero;
-
-            let mut updated_values = Vec::new();
-            for value in &values_to_process {
-                let character_value_at_position: char = value.chars().nth(i).unwrap();
-
-                if (character_value_at_position == '1' && keep_values_starting_with_one)
-                    || (character_value_at_position == '0' && keep_values_starting_with_zero)
-                {
-                    updated_values.push(value.clone());
-                }
-            }
-            values_to_process = updated_values;
-
-            if values_to_process.len() == 1 {
-                oxygen_generator_rating_string = values_to_process[0].to_string();
-                break;
-            }
-        }
+        self.power_consumption = u128::from(self.gamma_rate) * u128::from(self.epsilon_rate);
+    }
 
-        self.oxygen_generator_rating =
-            isize::from_str_radix(&oxygen_generator_rating_string[..], 2).unwrap();
+    pub fn calculate_oxygen_generator_rating(
+        &mut self,
+        binary_values: &[String],
+    ) -> Result<(), DiagnosticError> {
+        let readings = Readings::parse(binary_values)?;
+        self.oxygen_generator_rating = rating_value(
+            &readings,
+            self.rating_strategy
+                .select(&readings, &BitCriteria::oxygen_generator()),
+        )?;
         self.calculate_life_support_rating();
+        Ok(())
     }
 
-    pub fn calculate_co2_scrubber_rating(&mut self, binary_values: &[String]) {
-        let mut co2_scrubber_rating_string: String = "".to_owned();
-        let mut values_to_process: Vec<String> = binary_values.to_owned();
-
-        for i in 0..binary_values[0].len() {
-            let mut count_of_one = 0;
-            let mut count_of_zero = 0;
-            for value in &values_to_process {
-                let processing_character = value.chars().nth(i).unwrap();
-                if processing_character == '1' {
-                    count_of_one += 1;
-                } else {
-                    count_of_zero += 1;
-                }
-            }
-
-            let keep_values_starting_with_one: bool = count_of_one < count_of_zero;
-            let keep_values_starting_with_zero: bool = count_of_one >= count_of_zero;
-
-            let mut updated_values = Vec::new();
-            for value in &values_to_process {
-                let character_value_at_position: char = value.chars().nth(i).unwrap();
+    pub fn calculate_co2_scrubber_rating(
+        &mut self,
+        binary_values: &[String],
+    ) -> Result<(), DiagnosticError> {
+        let readings = Readings::parse(binary_values)?;
+        self.co2_scrubber_rating = rating_value(
+            &readings,
+            self.rating_strategy
+                .select(&readings, &BitCriteria::co2_scrubber()),
+        )?;
+        self.calculate_life_support_rating();
+        Ok(())
+    }
 
-                if (character_value_at_position == '1' && keep_values_starting_with_one)
-                    || (character_value_at_position == '0' && keep_values_starting_with_zero)
-                {
-                    updated_values.push(value.clone());
-                }
+    pub fn calculate_life_support_ratings(
+        &mut self,
+        readings: &Readings,
+    ) -> Result<(), DiagnosticError> {
+        let (oxygen, co2) = match self.rating_strategy.for_readings(readings) {
+            RatingStrategy::Trie => {
+                let trie = ReadingTrie::new(readings);
+                (
+                    trie.select(&BitCriteria::oxygen_generator()),
+                    trie.select(&BitCriteria::co2_scrubber()),
+                )
             }
-            values_to_process = updated_values;
-
-            if values_to_process.len() == 1 {
-                co2_scrubber_rating_string = values_to_process[0].to_string();
-                break;
+            _ => {
+                let threads = available_threads();
+                (
+                    filter_readings_in_parallel(
+                        readings,
+                        &BitCriteria::oxygen_generator(),
+                        threads,
+                    ),
+                    filter_readings_in_parallel(readings, &BitCriteria::co2_scrubber(), threads),
+                )
             }
-        }
-
-        self.co2_scrubber_rating =
-            isize::from_str_radix(&co2_scrubber_rating_string[..], 2).unwrap();
+        };
+        let oxygen = rating_value(readings, oxygen)?;
+        self.co2_scrubber_rating = rating_value(readings, co2)?;
+        self.oxygen_generator_rating = oxygen;
         self.calculate_life_support_rating();
+        Ok(())
     }
 
     fn calculate_life_support_rating(&mut self) {
-        self.life_support_rating = self.oxygen_generator_rating * self.co2_scrubber_rating;
+        self.life_support_rating =
+            u128::from(self.oxygen_generator_rating) * u128::from(self.co2_scrubber_rating);
+    }
+}
+
+fn rate(
+    readings: &Readings,
+    ones_per_position: &[usize],
+    criteria: BitCriteria,
+) -> Result<u64, DiagnosticError> {
+    if readings.is_empty() {
+        return Err(DiagnosticError::NoReadings);
+    }
+    let bits = select_bits(ones_per_position, readings.len(), &criteria)?;
+    bits_to_value(bits).ok_or_else(|| too_wide(readings))
+}
+
+fn rating_value(
+    readings: &Readings,
+    selected: Result<usize, CriteriaError>,
+) -> Result<u64, DiagnosticError> {
+    let index = selected?;
+    bits_to_value((0..readings.width()).map(|p| readings.bit(index, p)))
+        .ok_or_else(|| too_wide(readings))
+}
+
+fn too_wide(readings: &Readings) -> DiagnosticError {
+    DiagnosticError::TooWide {
+        width: readings.width(),
+        radix: 2,
     }
 }
 
@@ -176,7 +231,7 @@ mod tests_day3a {
     use super::*;
 
     fn get_sample_data() -> Vec<String> {
-        return vec![
+        vec![
             "00100".to_owned(),
             "11110".to_owned(),
             "10110".to_owned(),
@@ -189,7 +244,7 @@ mod tests_day3a {
             "11001".to_owned(),
             "00010".to_owned(),
             "01010".to_owned(),
-        ];
+        ]
     }
 
     #[test]
@@ -202,44 +257,193 @@ mod tests_day3a {
     #[test]
     fn test_calculate_gamma() {
         let mut power_diagnostic = PowerDiagnostic::new();
-        power_diagnostic.calculate_gamma_rate(&get_sample_data());
+        power_diagnostic
+            .calculate_gamma_rate(&get_sample_data())
+            .unwrap();
         assert_eq!(power_diagnostic.gamma_rate, 22);
     }
 
     #[test]
     fn test_calculate_epsilon() {
         let mut power_diagnostic = PowerDiagnostic::new();
-        power_diagnostic.calculate_epsilon_rate(&get_sample_data());
+        power_diagnostic
+            .calculate_epsilon_rate(&get_sample_data())
+            .unwrap();
         assert_eq!(power_diagnostic.epsilon_rate, 9)
     }
 
     #[test]
     fn test_calculate_power_consumption() {
         let mut power_diagnostic = PowerDiagnostic::new();
-        power_diagnostic.calculate_gamma_rate(&get_sample_data());
-        power_diagnostic.calculate_epsilon_rate(&get_sample_data());
+        power_diagnostic
+            .calculate_gamma_rate(&get_sample_data())
+            .unwrap();
+        power_diagnostic
+            .calculate_epsilon_rate(&get_sample_data())
+            .unwrap();
         assert_eq!(power_diagnostic.power_consumption, 198);
     }
 
     #[test]
     fn test_calculate_oxygen_generator_rating() {
         let mut power_diagnostic = PowerDiagnostic::new();
-        power_diagnostic.calculate_oxygen_generator_rating(&get_sample_data());
+        power_diagnostic
+            .calculate_oxygen_generator_rating(&get_sample_data())
+            .unwrap();
         assert_eq!(power_diagnostic.oxygen_generator_rating, 23);
     }
 
     #[test]
     fn test_calculate_co2_scrubber_rating() {
         let mut power_diagnostic = PowerDiagnostic::new();
-        power_diagnostic.calculate_co2_scrubber_rating(&get_sample_data());
+        power_diagnostic
+            .calculate_co2_scrubber_rating(&get_sample_data())
+            .unwrap();
         assert_eq!(power_diagnostic.co2_scrubber_rating, 10);
     }
 
+    #[test]
+    fn test_calculate_from_packed_readings() {
+        let readings = Readings::from_strings(&get_sample_data());
+        let mut power_diagnostic = PowerDiagnostic::new();
+        power_diagnostic.calculate_power_rates(&readings).unwrap();
+        power_diagnostic
+            .calculate_life_support_ratings(&readings)
+            .unwrap();
+        assert_eq!(power_diagnostic.power_consumption, 198);
+        assert_eq!(power_diagnostic.life_support_rating, 230);
+    }
+
+    #[test]
+    fn test_rating_strategies_agree() {
+        let input = std::fs::read_to_string("input.txt").unwrap();
+        let lines: Vec<String> = input.lines().map(String::from).collect();
+        for data in [get_sample_data(), lines] {
+            let readings = Readings::from_strings(&data);
+            let mut trie = PowerDiagnostic::with_rating_strategy(RatingStrategy::Trie);
+            let mut filter = PowerDiagnostic::with_rating_strategy(RatingStrategy::Filter);
+            trie.calculate_life_support_ratings(&readings).unwrap();
+            filter.calculate_life_support_ratings(&readings).unwrap();
+            assert_eq!(trie.oxygen_generator_rating, filter.oxygen_generator_rating);
+            assert_eq!(trie.co2_scrubber_rating, filter.co2_scrubber_rating);
+        }
+    }
+
+    #[test]
+    fn test_custom_rating_definition() {
+        let readings = Readings::from_strings(&get_sample_data());
+        let strict = BitCriteria::new(Commonality::MostCommon, TieBreak::Error);
+        assert_eq!(
+            RatingStrategy::Trie.select(&readings, &strict),
+            Err(CriteriaError::Tie { position: 4 })
+        );
+
+        let ones_by_two_to_one =
+            |count_of_one: usize, count_of_zero: usize| count_of_one > 2 * count_of_zero;
+        let index = RatingStrategy::Filter
+            .select(&readings, &ones_by_two_to_one)
+            .unwrap();
+        assert_eq!(readings.to_binary_string(index), "00010");
+        assert_eq!(
+            RatingStrategy::Trie.select(&readings, &ones_by_two_to_one),
+            Ok(index)
+        );
+    }
+
+    #[test]
+    fn test_auto_rating_strategy() {
+        let small = Readings::from_strings(&get_sample_data());
+        assert_eq!(
+            RatingStrategy::Auto.for_readings(&small),
+            RatingStrategy::Trie
+        );
+        let values: Vec<u64> = (0..PARALLEL_THRESHOLD as u64).collect();
+        let large = Readings::from_values(&values, 14);
+        assert_eq!(
+            RatingStrategy::Auto.for_readings(&large),
+            RatingStrategy::Filter
+        );
+        assert_eq!(
+            RatingStrategy::Trie.for_readings(&large),
+            RatingStrategy::Trie
+        );
+    }
+
+    #[test]
+    fn test_auto_rating_strategy_above_threshold() {
+        let values: Vec<u64> = (0..PARALLEL_THRESHOLD as u64 * 2 + 5)
+            .map(|value| value * 7919 % 65536)
+            .collect();
+        let readings = Readings::from_values(&values, 16);
+        for criterion in [BitCriteria::oxygen_generator(), BitCriteria::co2_scrubber()] {
+            assert_eq!(
+                RatingStrategy::Auto.select(&readings, &criterion),
+                RatingStrategy::Trie.select(&readings, &criterion)
+            );
+        }
+
+        let mut auto = PowerDiagnostic::new();
+        let mut trie = PowerDiagnostic::with_rating_strategy(RatingStrategy::Trie);
+        auto.calculate_life_support_ratings(&readings).unwrap();
+        trie.calculate_life_support_ratings(&readings).unwrap();
+        assert_eq!(auto.life_support_rating, trie.life_support_rating);
+        assert_ne!(auto.life_support_rating, 0);
+    }
+
+    #[test]
+    fn test_report() {
+        let mut data = get_sample_data();
+        data.push("1012".to_owned());
+        let report = PowerDiagnostic::new().report(&data);
+        assert_eq!(report.readings, 12);
+        let result = report.result.unwrap();
+        assert_eq!(result.power, 198);
+        assert_eq!(result.life_support, 230);
+        assert_eq!(report.positions[0].ones, 7);
+        assert_eq!(report.ties, Vec::<usize>::new());
+        assert_eq!(report.errors[0].line_number, 13);
+    }
+
+    #[test]
+    fn test_calculation_errors_are_returned() {
+        let mut power_diagnostic = PowerDiagnostic::new();
+        power_diagnostic
+            .calculate_gamma_rate(&get_sample_data())
+            .unwrap();
+        assert_eq!(
+            power_diagnostic.calculate_gamma_rate(&[]),
+            Err(DiagnosticError::NoReadings)
+        );
+        assert_eq!(power_diagnostic.gamma_rate, 22);
+
+        let tied = Readings::from_strings(&["10", "01"]);
+        let mut ratings = PowerDiagnostic::new();
+        assert_eq!(
+            ratings.calculate_life_support_ratings(&Readings::new(2)),
+            Err(DiagnosticError::Criteria(CriteriaError::NoReadings))
+        );
+        ratings.calculate_life_support_ratings(&tied).unwrap();
+        assert_eq!(ratings.life_support_rating, 2);
+
+        let wide = Readings::from_strings(&[format!("1{}", "0".repeat(64))]);
+        assert_eq!(
+            ratings.calculate_power_rates(&wide),
+            Err(DiagnosticError::TooWide {
+                width: 65,
+                radix: 2
+            })
+        );
+    }
+
     #[test]
     fn test_calculate_life_support_rating() {
         let mut power_diagnostic = PowerDiagnostic::new();
-        power_diagnostic.calculate_oxygen_generator_rating(&get_sample_data());
-        power_diagnostic.calculate_co2_scrubber_rating(&get_sample_data());
+        power_diagnostic
+            .calculate_oxygen_generator_rating(&get_sample_data())
+            .unwrap();
+        power_diagnostic
+            .calculate_co2_scrubber_rating(&get_sample_data())
+            .unwrap();
         assert_eq!(power_diagnostic.life_support_rating, 230);
     }
 }
@@ -1,27 +1,77 @@
-use ::cmilbert_aoc_day3b::PowerDiagnostic;
+use ::cmilbert_aoc_day3b::{DiagnosticReport, Diagnostics, Readings};
 
-use std::{
-    fs::File,
-    io::{prelude::*, BufReader},
-    path::Path,
-};
+use std::{env, fs::File, io::BufReader, path::Path, process};
 
-fn read_lines_from_input_file(filename: impl AsRef<Path>) -> Vec<String> {
-    let file = File::open(filename).expect("no such file");
-    let buf = BufReader::new(file);
-    buf.lines()
-        .map(|l| l.expect("Could not parse line"))
-        .collect()
+fn open_input_file(filename: impl AsRef<Path>) -> BufReader<File> {
+    BufReader::new(File::open(filename).expect("no such file"))
+}
+
+fn print_report(report: DiagnosticReport) {
+    println!("Readings: {} ({} bits wide)", report.readings, report.width);
+    for statistics in &report.positions {
+        println!(
+            "Position {}: {} ones, {} zeros, balance {:.3}, entropy {:.3}",
+            statistics.position,
+            statistics.ones,
+            statistics.zeros,
+            statistics.balance,
+            statistics.entropy
+        );
+    }
+    if !report.ties.is_empty() {
+        let ties: Vec<String> = report.ties.iter().map(usize::to_string).collect();
+        println!("Tied positions: {}", ties.join(", "));
+    }
+    match report.result {
+        Ok(result) => {
+            println!(
+                "Gamma rate: {}, epsilon rate: {}, power consumption: {}",
+                result.gamma, result.epsilon, result.power
+            );
+            println!(
+                "Oxygen generator rating: {}, CO2 scrubber rating: {}, life support rating: {}",
+                result.oxygen, result.co2, result.life_support
+            );
+        }
+        Err(error) => println!("Diagnostics failed: {}", error),
+    }
+    for error in &report.errors {
+        println!("Invalid reading on {}", error);
+    }
 }
 
 fn main() {
-    let mut power_diagnostic = PowerDiagnostic::new();
-    let binary_input = read_lines_from_input_file("input.txt");
+    let diagnostics = Diagnostics::default();
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    let result = match args.as_slice() {
+        [flag, radix] if flag == "--radix" => {
+            let radix = radix.parse().unwrap_or_else(|_| {
+                eprintln!("invalid radix: {}", radix);
+                process::exit(1);
+            });
+            Diagnostics::read_radix(open_input_file("input.txt"), radix)
+                .expect("Could not read input")
+        }
+        _ => {
+            let (readings, errors) =
+                Readings::read_valid(open_input_file("input.txt")).expect("Could not read input");
+            if args.first().map(String::as_str) == Some("--report") {
+                print_report(DiagnosticReport::new(&readings, errors, diagnostics));
+                return;
+            }
+            match errors.into_iter().next() {
+                Some(error) => Err(error.into()),
+                None => diagnostics.evaluate(&readings),
+            }
+        }
+    };
 
-    power_diagnostic.calculate_oxygen_generator_rating(&binary_input);
-    power_diagnostic.calculate_co2_scrubber_rating(&binary_input);
-    println!(
-        "Life support rating: {}",
-        power_diagnostic.life_support_rating
-    );
+    match result {
+        Ok(result) => println!("Life support rating: {}", result.life_support),
+        Err(error) => {
+            eprintln!("input.txt: {}", error);
+            process::exit(1);
+        }
+    }
 }
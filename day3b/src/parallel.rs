@@ -0,0 +1,106 @@
+use std::{num::NonZeroUsize, ops::Range, thread};
+
+// Below this many items a single thread is faster than spawning more.
+pub(crate) const PARALLEL_THRESHOLD: usize = 1 << 14;
+
+pub(crate) fn available_threads() -> usize {
+    thread::available_parallelism().map_or(1, NonZeroUsize::get)
+}
+
+// Splits `0..len` into at most `threads` contiguous ranges, counts each range
+// on its own scoped thread and sums the counts element-wise.
+pub(crate) fn sum_counts(
+    len: usize,
+    threads: usize,
+    count: impl Fn(Range<usize>) -> Vec<usize> + Sync,
+) -> Vec<usize> {
+    if threads <= 1 || len < PARALLEL_THRESHOLD {
+        return count(0..len);
+    }
+    let chunk_size = len.div_ceil(threads);
+    let count = &count;
+    let partial_counts: Vec<Vec<usize>> = thread::scope(|scope| {
+        let handles: Vec<_> = (0..len)
+            .step_by(chunk_size)
+            .map(|start| scope.spawn(move || count(start..(start + chunk_size).min(len))))
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("counting thread panicked"))
+            .collect()
+    });
+
+    let mut totals = Vec::new();
+    for counts in partial_counts {
+        if totals.len() < counts.len() {
+            totals.resize(counts.len(), 0);
+        }
+        for (total, count) in totals.iter_mut().zip(counts) {
+            *total += count;
+        }
+    }
+    totals
+}
+
+// Keeps the values `keep` accepts, in order, testing up to `threads`
+// contiguous chunks on their own scoped threads.
+pub(crate) fn retain_in_parallel<T: Copy + Send + Sync>(
+    values: &mut Vec<T>,
+    threads: usize,
+    keep: impl Fn(&T) -> bool + Sync,
+) {
+    if threads <= 1 || values.len() < PARALLEL_THRESHOLD {
+        values.retain(keep);
+        return;
+    }
+    let chunk_size = values.len().div_ceil(threads);
+    let keep = &keep;
+    let kept: Vec<T> = thread::scope(|scope| {
+        let handles: Vec<_> = values
+            .chunks(chunk_size)
+            .map(|chunk| {
+                scope.spawn(move || chunk.iter().copied().filter(keep).collect::<Vec<T>>())
+            })
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().expect("filtering thread panicked"))
+            .collect()
+    });
+    *values = kept;
+}
+
+#[cfg(test)]
+mod tests_parallel {
+    use super::*;
+
+    #[test]
+    fn test_sum_counts() {
+        let len = PARALLEL_THRESHOLD * 3 + 7;
+        let count_parity = |range: Range<usize>| {
+            let mut counts = vec![0, 0];
+            for value in range {
+                counts[value % 2] += 1;
+            }
+            counts
+        };
+        let sequential = sum_counts(len, 1, count_parity);
+        assert_eq!(sequential, vec![len / 2 + 1, len / 2]);
+        assert_eq!(sum_counts(len, 4, count_parity), sequential);
+        assert_eq!(sum_counts(len, 1000, count_parity), sequential);
+        assert_eq!(sum_counts(0, 4, count_parity), vec![0, 0]);
+    }
+
+    #[test]
+    fn test_retain_in_parallel() {
+        let values: Vec<usize> = (0..PARALLEL_THRESHOLD * 3 + 7).collect();
+        let mut sequential = values.clone();
+        retain_in_parallel(&mut sequential, 1, |value| value % 3 == 1);
+        assert_eq!(sequential.len(), PARALLEL_THRESHOLD + 2);
+        for threads in [4, 1000] {
+            let mut parallel = values.clone();
+            retain_in_parallel(&mut parallel, threads, |value| value % 3 == 1);
+            assert_eq!(parallel, sequential);
+        }
+    }
+}
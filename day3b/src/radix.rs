@@ -0,0 +1,301 @@
+use crate::{
+    criteria::{BitCriteria, Commonality, CriteriaError, TieBreak},
+    readings::{validate, ReadingError},
+    results::{DiagnosticError, DiagnosticResult},
+};
+use std::{convert::Infallible, io};
+
+// Readings in any radix from 2 to 36, one digit per byte. Binary readings are
+// better served by the packed `Readings`; this is the general path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RadixReadings {
+    radix: u32,
+    width: usize,
+    digits: Vec<u8>,
+}
+
+impl RadixReadings {
+    pub fn parse<S: AsRef<str>>(values: &[S], radix: u32) -> Result<Self, DiagnosticError> {
+        let Ok(parsed) = RadixReadings::parse_lines(values.iter().map(Ok::<_, Infallible>), radix);
+        parsed
+    }
+
+    // Same as `parse`, but stores each line's digits as it is read.
+    pub fn read(reader: impl io::BufRead, radix: u32) -> io::Result<Result<Self, DiagnosticError>> {
+        RadixReadings::parse_lines(reader.lines(), radix)
+    }
+
+    // The width is taken from the first non-blank line.
+    fn parse_lines<S: AsRef<str>, E>(
+        lines: impl IntoIterator<Item = Result<S, E>>,
+        radix: u32,
+    ) -> Result<Result<Self, DiagnosticError>, E> {
+        if !(2..=36).contains(&radix) {
+            return Ok(Err(DiagnosticError::InvalidRadix(radix)));
+        }
+        let mut width = None;
+        let mut digits = Vec::new();
+        for (index, line) in lines.into_iter().enumerate() {
+            let line = line?;
+            let value = line.as_ref().trim();
+            if value.is_empty() {
+                continue;
+            }
+            let width = *width.get_or_insert(value.len());
+            if let Err(kind) = validate(value, width, radix) {
+                return Ok(Err(ReadingError {
+                    line_number: index + 1,
+                    kind,
+                }
+                .into()));
+            }
+            digits.extend(
+                value
+                    .chars()
+                    .filter_map(|c| c.to_digit(radix))
+                    .map(|d| d as u8),
+            );
+        }
+        Ok(Ok(RadixReadings {
+            radix,
+            width: width.unwrap_or(0),
+            digits,
+        }))
+    }
+
+    pub fn radix(&self) -> u32 {
+        self.radix
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn len(&self) -> usize {
+        self.digits.len().checked_div(self.width).unwrap_or(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn digit(&self, index: usize, position: usize) -> u32 {
+        u32::from(self.digits[index * self.width + position])
+    }
+
+    pub fn value(&self, index: usize) -> Option<u64> {
+        to_value(
+            (0..self.width).map(|position| self.digit(index, position)),
+            self.radix,
+        )
+    }
+
+    pub fn digit_counts(&self) -> Vec<Vec<usize>> {
+        let all: Vec<usize> = (0..self.len()).collect();
+        (0..self.width)
+            .map(|position| self.digit_counts_of(&all, position))
+            .collect()
+    }
+
+    fn digit_counts_of(&self, indices: &[usize], position: usize) -> Vec<usize> {
+        let mut counts = vec![0; self.radix as usize];
+        for &index in indices {
+            counts[self.digit(index, position) as usize] += 1;
+        }
+        counts
+    }
+
+    // Readings of width w in base b fit in a u64 when b^w - 1 does.
+    fn fits_in_u64(&self) -> bool {
+        u32::try_from(self.width)
+            .ok()
+            .and_then(|width| u128::from(self.radix).checked_pow(width))
+            .is_some_and(|limit| limit <= 1 << 64)
+    }
+
+    pub fn evaluate(&self) -> Result<DiagnosticResult, DiagnosticError> {
+        if !self.fits_in_u64() {
+            return Err(DiagnosticError::TooWide {
+                width: self.width,
+                radix: self.radix,
+            });
+        }
+        if self.is_empty() {
+            return Err(DiagnosticError::NoReadings);
+        }
+
+        let digit_counts = self.digit_counts();
+        let select_all = |criteria: BitCriteria| -> Result<Vec<u32>, CriteriaError> {
+            digit_counts
+                .iter()
+                .enumerate()
+                .map(|(position, counts)| select_digit(counts, position, &criteria, false))
+                .collect()
+        };
+        let gamma = to_value(select_all(BitCriteria::gamma())?, self.radix).unwrap_or_default();
+        let epsilon = to_value(select_all(BitCriteria::epsilon())?, self.radix).unwrap_or_default();
+        let oxygen = self
+            .value(filter_radix_readings(
+                self,
+                &BitCriteria::oxygen_generator(),
+            )?)
+            .unwrap_or_default();
+        let co2 = self
+            .value(filter_radix_readings(self, &BitCriteria::co2_scrubber())?)
+            .unwrap_or_default();
+
+        Ok(DiagnosticResult {
+            gamma,
+            epsilon,
+            power: u128::from(gamma) * u128::from(epsilon),
+            oxygen,
+            co2,
+            life_support: u128::from(oxygen) * u128::from(co2),
+        })
+    }
+}
+
+fn to_value(digits: impl IntoIterator<Item = u32>, radix: u32) -> Option<u64> {
+    digits.into_iter().try_fold(0u64, |value, digit| {
+        value
+            .checked_mul(u64::from(radix))?
+            .checked_add(u64::from(digit))
+    })
+}
+
+// Picks the most or least common digit at a position. Ties go to the lowest
+// tied digit for `TieBreak::Zero` and the highest for `TieBreak::One`, which
+// in base 2 is exactly the bit criterion. When `present_only` is set, digits
+// that do not occur are not candidates, so filtering can never empty the set.
+pub fn select_digit(
+    counts: &[usize],
+    position: usize,
+    criteria: &BitCriteria,
+    present_only: bool,
+) -> Result<u32, CriteriaError> {
+    let candidates = || (0..counts.len()).filter(|&digit| !present_only || counts[digit] > 0);
+    let target = match criteria.commonality {
+        Commonality::MostCommon => candidates().map(|digit| counts[digit]).max(),
+        Commonality::LeastCommon => candidates().map(|digit| counts[digit]).min(),
+    }
+    .ok_or(CriteriaError::NoReadings)?;
+    let tied: Vec<usize> = candidates()
+        .filter(|&digit| counts[digit] == target)
+        .collect();
+
+    let digit = match (tied.len(), criteria.tie_break) {
+        (1, _) | (_, TieBreak::Zero) => tied[0],
+        (_, TieBreak::One) => tied[tied.len() - 1],
+        (_, TieBreak::Error) => return Err(CriteriaError::Tie { position }),
+    };
+    Ok(digit as u32)
+}
+
+pub fn filter_radix_readings(
+    readings: &RadixReadings,
+    criteria: &BitCriteria,
+) -> Result<usize, CriteriaError> {
+    let mut values_to_process: Vec<usize> = (0..readings.len()).collect();
+    if values_to_process.is_empty() {
+        return Err(CriteriaError::NoReadings);
+    }
+
+    for position in 0..readings.width() {
+        if values_to_process.len() == 1 {
+            break;
+        }
+        let counts = readings.digit_counts_of(&values_to_process, position);
+        let keep = select_digit(&counts, position, criteria, true)?;
+        values_to_process.retain(|&index| readings.digit(index, position) == keep);
+    }
+
+    Ok(values_to_process[0])
+}
+
+#[cfg(test)]
+mod tests_radix {
+    use super::*;
+    use crate::Diagnostics;
+
+    fn get_sample_data() -> Vec<&'static str> {
+        vec![
+            "00100", "11110", "10110", "10111", "10101", "01111", "00111", "11100", "10000",
+            "11001", "00010", "01010",
+        ]
+    }
+
+    #[test]
+    fn test_binary_matches_fast_path() {
+        let readings = RadixReadings::parse(&get_sample_data(), 2).unwrap();
+        assert_eq!(
+            readings.evaluate(),
+            Diagnostics::from_strings(&get_sample_data())
+        );
+    }
+
+    #[test]
+    fn test_ternary_readings() {
+        let readings = RadixReadings::parse(&["012", "112", "210", "111", "022"], 3).unwrap();
+        assert_eq!(readings.len(), 5);
+        assert_eq!(readings.value(0), Some(5));
+        assert_eq!(readings.digit_counts()[0], vec![2, 2, 1]);
+
+        let result = readings.evaluate().unwrap();
+        // Most common digits 0|1, 1, 2 with ties to the lowest: "012".
+        assert_eq!(result.gamma, 5);
+        // Least common digits 2, 0, 0|1 with ties to the lowest: "200".
+        assert_eq!(result.epsilon, 18);
+        // Oxygen keeps 1s then 1s then 2s: "112".
+        assert_eq!(result.oxygen, 14);
+        // CO2 keeps 2s at the first position: "210".
+        assert_eq!(result.co2, 21);
+    }
+
+    #[test]
+    fn test_hex_readings() {
+        let readings = RadixReadings::parse(&["ff", "0A", "f0"], 16).unwrap();
+        let result = readings.evaluate().unwrap();
+        assert_eq!(result.gamma, 0xf0);
+        assert_eq!(result.oxygen, 0xff);
+    }
+
+    #[test]
+    fn test_select_digit() {
+        let counts = [3, 0, 3, 1];
+        let most_common_high = BitCriteria::new(Commonality::MostCommon, TieBreak::One);
+        assert_eq!(select_digit(&counts, 0, &most_common_high, false), Ok(2));
+        assert_eq!(
+            select_digit(&counts, 0, &BitCriteria::co2_scrubber(), false),
+            Ok(1)
+        );
+        assert_eq!(
+            select_digit(&counts, 0, &BitCriteria::co2_scrubber(), true),
+            Ok(3)
+        );
+        let strict = BitCriteria::new(Commonality::MostCommon, TieBreak::Error);
+        assert_eq!(
+            select_digit(&counts, 7, &strict, false),
+            Err(CriteriaError::Tie { position: 7 })
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            RadixReadings::parse(&["12"], 40),
+            Err(DiagnosticError::InvalidRadix(40))
+        );
+        assert!(matches!(
+            RadixReadings::parse(&["12", "13"], 3),
+            Err(DiagnosticError::Reading(_))
+        ));
+        let wide = RadixReadings::parse(&["1".repeat(41)], 3).unwrap();
+        assert_eq!(
+            wide.evaluate(),
+            Err(DiagnosticError::TooWide {
+                width: 41,
+                radix: 3
+            })
+        );
+    }
+}
@@ -0,0 +1,347 @@
+use crate::parallel::sum_counts;
+use std::{convert::Infallible, error, fmt, io};
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReadingErrorKind {
+    MismatchedWidth { expected: usize, found: usize },
+    InvalidCharacter { character: char, position: usize },
+}
+
+impl fmt::Display for ReadingErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReadingErrorKind::MismatchedWidth { expected, found } => write!(
+                f,
+                "expected a reading {} digits wide but found {}",
+                expected, found
+            ),
+            ReadingErrorKind::InvalidCharacter {
+                character,
+                position,
+            } => write!(
+                f,
+                "invalid character {:?} at position {}",
+                character, position
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReadingError {
+    pub line_number: usize,
+    pub kind: ReadingErrorKind,
+}
+
+impl fmt::Display for ReadingError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line_number, self.kind)
+    }
+}
+
+impl error::Error for ReadingError {}
+
+pub(crate) fn validate(value: &str, width: usize, radix: u32) -> Result<(), ReadingErrorKind> {
+    if let Some((position, character)) = value
+        .chars()
+        .enumerate()
+        .find(|(_, character)| !character.is_digit(radix))
+    {
+        return Err(ReadingErrorKind::InvalidCharacter {
+            character,
+            position,
+        });
+    }
+    if value.len() != width {
+        return Err(ReadingErrorKind::MismatchedWidth {
+            expected: width,
+            found: value.len(),
+        });
+    }
+    Ok(())
+}
+
+// Each reading is packed into `words_per_reading` words, least significant
+// word first, so a reading up to 64 bits wide is simply its integer value.
+// Positions are counted from the left of the original string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Readings {
+    width: usize,
+    words_per_reading: usize,
+    words: Vec<u64>,
+}
+
+impl Readings {
+    pub fn new(width: usize) -> Self {
+        Readings {
+            width,
+            words_per_reading: width.div_ceil(WORD_BITS).max(1),
+            words: Vec::new(),
+        }
+    }
+
+    pub fn from_strings<S: AsRef<str>>(binary_values: &[S]) -> Self {
+        let width = binary_values
+            .first()
+            .map_or(0, |value| value.as_ref().len());
+        let mut readings = Readings::new(width);
+        for value in binary_values {
+            readings.push_str(value.as_ref());
+        }
+        readings
+    }
+
+    pub fn parse<S: AsRef<str>>(binary_values: &[S]) -> Result<Self, ReadingError> {
+        let (readings, errors) = Readings::parse_valid(binary_values);
+        match errors.into_iter().next() {
+            Some(error) => Err(error),
+            None => Ok(readings),
+        }
+    }
+
+    // Blank lines are skipped. The width is taken from the first reading, and
+    // every reading that does not match it or is not binary is left out and
+    // reported instead.
+    pub fn parse_valid<S: AsRef<str>>(binary_values: &[S]) -> (Self, Vec<ReadingError>) {
+        let Ok(parsed) = Readings::parse_lines(binary_values.iter().map(Ok::<_, Infallible>));
+        parsed
+    }
+
+    // Same as `parse_valid`, but packs each line as it is read so a large
+    // dump is never held in memory as strings.
+    pub fn read_valid(reader: impl io::BufRead) -> io::Result<(Self, Vec<ReadingError>)> {
+        Readings::parse_lines(reader.lines())
+    }
+
+    fn parse_lines<S: AsRef<str>, E>(
+        lines: impl IntoIterator<Item = Result<S, E>>,
+    ) -> Result<(Self, Vec<ReadingError>), E> {
+        let mut readings: Option<Readings> = None;
+        let mut errors = Vec::new();
+        for (index, line) in lines.into_iter().enumerate() {
+            let line = line?;
+            let value = line.as_ref().trim();
+            if value.is_empty() {
+                continue;
+            }
+            let readings = readings.get_or_insert_with(|| Readings::new(value.len()));
+            match validate(value, readings.width, 2) {
+                Ok(()) => readings.push_str(value),
+                Err(kind) => errors.push(ReadingError {
+                    line_number: index + 1,
+                    kind,
+                }),
+            }
+        }
+        Ok((readings.unwrap_or_else(|| Readings::new(0)), errors))
+    }
+
+    pub fn from_values(values: &[u64], width: usize) -> Self {
+        assert!(
+            width <= WORD_BITS,
+            "readings wider than 64 bits need from_strings"
+        );
+        Readings {
+            width,
+            words_per_reading: 1,
+            words: values.to_vec(),
+        }
+    }
+
+    fn push_str(&mut self, binary_value: &str) {
+        let start = self.words.len();
+        self.words.resize(start + self.words_per_reading, 0);
+        for (position, character) in binary_value.bytes().take(self.width).enumerate() {
+            if character == b'1' {
+                let significance = self.width - 1 - position;
+                self.words[start + significance / WORD_BITS] |= 1 << (significance % WORD_BITS);
+            }
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.len() / self.words_per_reading
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    fn reading(&self, index: usize) -> &[u64] {
+        let start = index * self.words_per_reading;
+        &self.words[start..start + self.words_per_reading]
+    }
+
+    pub fn bit(&self, index: usize, position: usize) -> bool {
+        let significance = self.width - 1 - position;
+        self.reading(index)[significance / WORD_BITS] >> (significance % WORD_BITS) & 1 == 1
+    }
+
+    pub fn value(&self, index: usize) -> Option<u64> {
+        let reading = self.reading(index);
+        if reading[1..].iter().any(|&word| word != 0) {
+            return None;
+        }
+        Some(reading[0])
+    }
+
+    pub fn to_binary_string(&self, index: usize) -> String {
+        (0..self.width)
+            .map(|position| if self.bit(index, position) { '1' } else { '0' })
+            .collect()
+    }
+
+    pub fn ones_per_position(&self) -> Vec<usize> {
+        self.ones_per_position_of(0..self.len())
+    }
+
+    // Counts contiguous chunks of readings on up to `threads` threads and
+    // merges the per-chunk counts.
+    pub fn ones_per_position_in_parallel(&self, threads: usize) -> Vec<usize> {
+        let ones_by_significance = sum_counts(self.len(), threads, |indices| {
+            self.ones_by_significance(indices)
+        });
+        self.by_position(&ones_by_significance)
+    }
+
+    pub fn ones_per_position_of(&self, indices: impl IntoIterator<Item = usize>) -> Vec<usize> {
+        self.by_position(&self.ones_by_significance(indices))
+    }
+
+    // Walks only the set bits of each word, so sparse readings cost less than
+    // their width.
+    fn ones_by_significance(&self, indices: impl IntoIterator<Item = usize>) -> Vec<usize> {
+        let mut ones_by_significance = vec![0; self.words_per_reading * WORD_BITS];
+        for index in indices {
+            for (word_index, &word) in self.reading(index).iter().enumerate() {
+                let mut remaining = word;
+                while remaining != 0 {
+                    let bit = remaining.trailing_zeros() as usize;
+                    ones_by_significance[word_index * WORD_BITS + bit] += 1;
+                    remaining &= remaining - 1;
+                }
+            }
+        }
+        ones_by_significance
+    }
+
+    fn by_position(&self, ones_by_significance: &[usize]) -> Vec<usize> {
+        (0..self.width)
+            .map(|position| ones_by_significance[self.width - 1 - position])
+            .collect()
+    }
+}
+
+// None when the value does not fit in 64 bits.
+pub fn bits_to_value(bits: impl IntoIterator<Item = bool>) -> Option<u64> {
+    bits.into_iter().try_fold(0u64, |value, bit| {
+        (value.leading_zeros() > 0).then(|| (value << 1) | u64::from(bit))
+    })
+}
+
+#[cfg(test)]
+mod tests_readings {
+    use super::*;
+
+    #[test]
+    fn test_pack_readings() {
+        let readings = Readings::from_strings(&["00100", "11110", "10110"]);
+        assert_eq!(readings.width(), 5);
+        assert_eq!(readings.len(), 3);
+        assert_eq!(readings.value(1), Some(0b11110));
+        assert!(readings.bit(2, 0));
+        assert!(!readings.bit(2, 1));
+        assert_eq!(readings.to_binary_string(0), "00100");
+        assert_eq!(readings.ones_per_position(), vec![2, 1, 3, 2, 0]);
+    }
+
+    #[test]
+    fn test_pack_wide_readings() {
+        let wide = format!("1{}1", "0".repeat(98));
+        let readings = Readings::from_strings(&[wide.clone(), "0".repeat(100)]);
+        assert_eq!(readings.len(), 2);
+        assert_eq!(readings.value(0), None);
+        assert_eq!(readings.value(1), Some(0));
+        assert_eq!(readings.to_binary_string(0), wide);
+
+        let ones = readings.ones_per_position();
+        assert_eq!(ones.len(), 100);
+        assert_eq!(ones.iter().sum::<usize>(), 2);
+        assert_eq!((ones[0], ones[99]), (1, 1));
+    }
+
+    #[test]
+    fn test_parse_validates_readings() {
+        let (readings, errors) = Readings::parse_valid(&["0101", "", "01", "0121", "1100 "]);
+        assert_eq!(readings.len(), 2);
+        assert_eq!(readings.value(1), Some(0b1100));
+        assert_eq!(
+            errors,
+            vec![
+                ReadingError {
+                    line_number: 3,
+                    kind: ReadingErrorKind::MismatchedWidth {
+                        expected: 4,
+                        found: 2
+                    }
+                },
+                ReadingError {
+                    line_number: 4,
+                    kind: ReadingErrorKind::InvalidCharacter {
+                        character: '2',
+                        position: 2
+                    }
+                },
+            ]
+        );
+        assert_eq!(
+            Readings::parse(&["0101", "0x01"]).unwrap_err().to_string(),
+            "line 2: invalid character 'x' at position 1"
+        );
+        assert_eq!(Readings::parse(&["0101", "1111"]).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_read_valid() {
+        let input = "0101\n\n01\n0121\n1100 \n";
+        let (readings, errors) = Readings::read_valid(io::Cursor::new(input)).unwrap();
+        assert_eq!(
+            (readings, errors),
+            Readings::parse_valid(&["0101", "", "01", "0121", "1100 "])
+        );
+        let (empty, errors) = Readings::read_valid(io::Cursor::new("\n\n")).unwrap();
+        assert!(empty.is_empty());
+        assert!(errors.is_empty());
+    }
+
+    #[test]
+    fn test_ones_per_position_in_parallel() {
+        let values: Vec<u64> = (0..100_000u64).map(|value| value * 7919 % 4096).collect();
+        let readings = Readings::from_values(&values, 12);
+        assert_eq!(
+            readings.ones_per_position_in_parallel(4),
+            readings.ones_per_position()
+        );
+
+        let wide = format!("1{}1", "0".repeat(98));
+        let readings = Readings::from_strings(&vec![wide; 20_000]);
+        let ones = readings.ones_per_position_in_parallel(3);
+        assert_eq!((ones[0], ones[1], ones[99]), (20_000, 0, 20_000));
+    }
+
+    #[test]
+    fn test_from_values() {
+        let readings = Readings::from_values(&[0b00100, 0b11110], 5);
+        assert_eq!(readings, Readings::from_strings(&["00100", "11110"]));
+        assert_eq!(bits_to_value([true, false, true, true, false]), Some(22));
+        assert_eq!(bits_to_value([true; 64]), Some(u64::MAX));
+        assert_eq!(bits_to_value([true; 65]), None);
+        assert_eq!(bits_to_value([false; 70]), Some(0));
+    }
+}
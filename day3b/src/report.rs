@@ -0,0 +1,115 @@
+use crate::{
+    readings::{ReadingError, Readings},
+    results::{DiagnosticError, DiagnosticResult, Diagnostics},
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BitStatistics {
+    pub position: usize,
+    pub ones: usize,
+    pub zeros: usize,
+    // Fraction of readings with a one; 0.5 is perfectly balanced.
+    pub balance: f64,
+    pub entropy: f64,
+}
+
+impl BitStatistics {
+    fn new(position: usize, ones: usize, total: usize) -> Self {
+        let (balance, entropy) = if total == 0 {
+            (0.0, 0.0)
+        } else {
+            let balance = ones as f64 / total as f64;
+            (balance, binary_entropy(balance))
+        };
+        BitStatistics {
+            position,
+            ones,
+            zeros: total - ones,
+            balance,
+            entropy,
+        }
+    }
+
+    pub fn is_tie(&self) -> bool {
+        self.ones == self.zeros && self.ones > 0
+    }
+}
+
+fn binary_entropy(probability: f64) -> f64 {
+    [probability, 1.0 - probability]
+        .iter()
+        .filter(|&&p| p > 0.0)
+        .map(|&p| -p * p.log2())
+        .sum()
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiagnosticReport {
+    pub readings: usize,
+    pub width: usize,
+    pub positions: Vec<BitStatistics>,
+    pub ties: Vec<usize>,
+    pub result: Result<DiagnosticResult, DiagnosticError>,
+    pub errors: Vec<ReadingError>,
+}
+
+impl DiagnosticReport {
+    pub fn new(readings: &Readings, errors: Vec<ReadingError>, diagnostics: Diagnostics) -> Self {
+        let ones_per_position = readings.ones_per_position_in_parallel(diagnostics.threads());
+        let positions: Vec<BitStatistics> = ones_per_position
+            .iter()
+            .enumerate()
+            .map(|(position, &ones)| BitStatistics::new(position, ones, readings.len()))
+            .collect();
+        let ties = positions
+            .iter()
+            .filter(|statistics| statistics.is_tie())
+            .map(|statistics| statistics.position)
+            .collect();
+
+        DiagnosticReport {
+            readings: readings.len(),
+            width: readings.width(),
+            positions,
+            ties,
+            result: diagnostics.evaluate_with_counts(readings, &ones_per_position),
+            errors,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests_report {
+    use super::*;
+
+    #[test]
+    fn test_binary_entropy() {
+        assert_eq!(binary_entropy(0.5), 1.0);
+        assert_eq!(binary_entropy(0.0), 0.0);
+        assert_eq!(binary_entropy(1.0), 0.0);
+        assert!((binary_entropy(0.25) - 0.811278).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_report() {
+        let (readings, errors) = Readings::parse_valid(&["1010", "1001", "0110", "1111", "10"]);
+        let report = DiagnosticReport::new(&readings, errors, Diagnostics::default());
+        assert_eq!(report.readings, 4);
+        assert_eq!(report.width, 4);
+        assert_eq!(report.positions[0].ones, 3);
+        assert_eq!(report.positions[0].zeros, 1);
+        assert_eq!(report.positions[0].balance, 0.75);
+        assert_eq!(report.positions[1].entropy, 1.0);
+        assert_eq!(report.ties, vec![1, 3]);
+        assert_eq!(report.errors.len(), 1);
+        assert_eq!(report.errors[0].line_number, 5);
+    }
+
+    #[test]
+    fn test_empty_report() {
+        let report = DiagnosticReport::new(&Readings::new(0), Vec::new(), Diagnostics::default());
+        assert_eq!(report.readings, 0);
+        assert!(report.positions.is_empty());
+        assert_eq!(report.result, Err(DiagnosticError::NoReadings));
+    }
+}
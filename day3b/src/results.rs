@@ -0,0 +1,328 @@
+use crate::{
+    criteria::{filter_readings_in_parallel, select_bits, BitCriteria, CriteriaError},
+    parallel::available_threads,
+    readings::{ReadingError, ReadingErrorKind, Readings},
+    DiagnosticReport, RadixReadings, RatingStrategy, ReadingTrie,
+};
+use std::{error, fmt, io};
+
+const MAXIMUM_WIDTH: usize = u64::BITS as usize;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiagnosticError {
+    Reading(ReadingError),
+    NoReadings,
+    InvalidRadix(u32),
+    TooWide { width: usize, radix: u32 },
+    Criteria(CriteriaError),
+}
+
+impl fmt::Display for DiagnosticError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DiagnosticError::Reading(error) => write!(f, "{}", error),
+            DiagnosticError::NoReadings => write!(f, "there are no readings"),
+            DiagnosticError::InvalidRadix(radix) => {
+                write!(f, "radix {} is not between 2 and 36", radix)
+            }
+            DiagnosticError::TooWide { width, radix } => write!(
+                f,
+                "readings {} digits wide in base {} do not fit in 64 bits",
+                width, radix
+            ),
+            DiagnosticError::Criteria(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl error::Error for DiagnosticError {}
+
+impl From<ReadingError> for DiagnosticError {
+    fn from(error: ReadingError) -> Self {
+        DiagnosticError::Reading(error)
+    }
+}
+
+impl From<CriteriaError> for DiagnosticError {
+    fn from(error: CriteriaError) -> Self {
+        DiagnosticError::Criteria(error)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DiagnosticResult {
+    pub gamma: u64,
+    pub epsilon: u64,
+    pub power: u128,
+    pub oxygen: u64,
+    pub co2: u64,
+    pub life_support: u128,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Diagnostics {
+    rating_strategy: RatingStrategy,
+    // Every available core when unset.
+    threads: Option<usize>,
+}
+
+impl Diagnostics {
+    pub fn with_rating_strategy(rating_strategy: RatingStrategy) -> Self {
+        Diagnostics {
+            rating_strategy,
+            threads: None,
+        }
+    }
+
+    pub fn with_threads(self, threads: usize) -> Self {
+        Diagnostics {
+            threads: Some(threads.max(1)),
+            ..self
+        }
+    }
+
+    pub fn threads(&self) -> usize {
+        self.threads.unwrap_or_else(available_threads)
+    }
+
+    pub fn from_readings(
+        values: &[u64],
+        width: usize,
+    ) -> Result<DiagnosticResult, DiagnosticError> {
+        if width > MAXIMUM_WIDTH {
+            return Err(DiagnosticError::TooWide { width, radix: 2 });
+        }
+        let significant_bits = |value: u64| (u64::BITS - value.leading_zeros()) as usize;
+        if let Some(index) = values.iter().position(|&v| significant_bits(v) > width) {
+            return Err(DiagnosticError::Reading(ReadingError {
+                line_number: index + 1,
+                kind: ReadingErrorKind::MismatchedWidth {
+                    expected: width,
+                    found: significant_bits(values[index]),
+                },
+            }));
+        }
+        Diagnostics::default().evaluate(&Readings::from_values(values, width))
+    }
+
+    pub fn from_strings<S: AsRef<str>>(
+        binary_values: &[S],
+    ) -> Result<DiagnosticResult, DiagnosticError> {
+        Diagnostics::default().evaluate(&Readings::parse(binary_values)?)
+    }
+
+    // Base 2 goes through the packed binary readings; other radixes use the
+    // digit-per-byte representation.
+    pub fn from_radix_strings<S: AsRef<str>>(
+        values: &[S],
+        radix: u32,
+    ) -> Result<DiagnosticResult, DiagnosticError> {
+        if radix == 2 {
+            return Diagnostics::from_strings(values);
+        }
+        RadixReadings::parse(values, radix)?.evaluate()
+    }
+
+    // Same as `from_radix_strings`, but reads the readings line by line.
+    pub fn read_radix(
+        reader: impl io::BufRead,
+        radix: u32,
+    ) -> io::Result<Result<DiagnosticResult, DiagnosticError>> {
+        if radix == 2 {
+            let (readings, errors) = Readings::read_valid(reader)?;
+            return Ok(match errors.into_iter().next() {
+                Some(error) => Err(error.into()),
+                None => Diagnostics::default().evaluate(&readings),
+            });
+        }
+        Ok(RadixReadings::read(reader, radix)?.and_then(|readings| readings.evaluate()))
+    }
+
+    pub fn report<S: AsRef<str>>(&self, binary_values: &[S]) -> DiagnosticReport {
+        let (readings, errors) = Readings::parse_valid(binary_values);
+        DiagnosticReport::new(&readings, errors, *self)
+    }
+
+    pub fn evaluate(&self, readings: &Readings) -> Result<DiagnosticResult, DiagnosticError> {
+        let ones_per_position = readings.ones_per_position_in_parallel(self.threads());
+        self.evaluate_with_counts(readings, &ones_per_position)
+    }
+
+    // For callers that have already counted the ones at every position.
+    pub(crate) fn evaluate_with_counts(
+        &self,
+        readings: &Readings,
+        ones_per_position: &[usize],
+    ) -> Result<DiagnosticResult, DiagnosticError> {
+        if readings.width() > MAXIMUM_WIDTH {
+            return Err(DiagnosticError::TooWide {
+                width: readings.width(),
+                radix: 2,
+            });
+        }
+        if readings.is_empty() {
+            return Err(DiagnosticError::NoReadings);
+        }
+
+        let threads = self.threads();
+        let gamma = to_value(&select_bits(
+            ones_per_position,
+            readings.len(),
+            &BitCriteria::gamma(),
+        )?);
+        let epsilon = to_value(&select_bits(
+            ones_per_position,
+            readings.len(),
+            &BitCriteria::epsilon(),
+        )?);
+
+        let (oxygen, co2) = match self.rating_strategy.for_readings(readings) {
+            RatingStrategy::Trie => {
+                let trie = ReadingTrie::new(readings);
+                (
+                    trie.select(&BitCriteria::oxygen_generator())?,
+                    trie.select(&BitCriteria::co2_scrubber())?,
+                )
+            }
+            _ => (
+                filter_readings_in_parallel(readings, &BitCriteria::oxygen_generator(), threads)?,
+                filter_readings_in_parallel(readings, &BitCriteria::co2_scrubber(), threads)?,
+            ),
+        };
+        // Both indices are in range and every reading fits in one word.
+        let oxygen = readings.value(oxygen).unwrap_or_default();
+        let co2 = readings.value(co2).unwrap_or_default();
+
+        Ok(DiagnosticResult {
+            gamma,
+            epsilon,
+            power: u128::from(gamma) * u128::from(epsilon),
+            oxygen,
+            co2,
+            life_support: u128::from(oxygen) * u128::from(co2),
+        })
+    }
+}
+
+fn to_value(bits: &[bool]) -> u64 {
+    bits.iter()
+        .fold(0, |value, &bit| (value << 1) | u64::from(bit))
+}
+
+#[cfg(test)]
+mod tests_results {
+    use super::*;
+
+    fn get_sample_values() -> Vec<u64> {
+        vec![
+            0b00100, 0b11110, 0b10110, 0b10111, 0b10101, 0b01111, 0b00111, 0b11100, 0b10000,
+            0b11001, 0b00010, 0b01010,
+        ]
+    }
+
+    #[test]
+    fn test_from_readings() {
+        let result = Diagnostics::from_readings(&get_sample_values(), 5).unwrap();
+        assert_eq!(
+            result,
+            DiagnosticResult {
+                gamma: 22,
+                epsilon: 9,
+                power: 198,
+                oxygen: 23,
+                co2: 10,
+                life_support: 230,
+            }
+        );
+    }
+
+    #[test]
+    fn test_strategies_agree() {
+        let readings = Readings::from_values(&get_sample_values(), 5);
+        assert_eq!(
+            Diagnostics::with_rating_strategy(RatingStrategy::Trie).evaluate(&readings),
+            Diagnostics::with_rating_strategy(RatingStrategy::Filter).evaluate(&readings)
+        );
+    }
+
+    #[test]
+    fn test_threads_agree() {
+        let values: Vec<u64> = (0..40_000u64).map(|value| value * 7919 % 8192).collect();
+        let readings = Readings::from_values(&values, 13);
+        let single = Diagnostics::default().with_threads(1).evaluate(&readings);
+        for strategy in [
+            RatingStrategy::Auto,
+            RatingStrategy::Trie,
+            RatingStrategy::Filter,
+        ] {
+            let diagnostics = Diagnostics::with_rating_strategy(strategy).with_threads(4);
+            assert_eq!(diagnostics.threads(), 4);
+            assert_eq!(diagnostics.evaluate(&readings), single);
+        }
+    }
+
+    #[test]
+    fn test_full_width_readings() {
+        let result = Diagnostics::from_readings(&[u64::MAX, u64::MAX, 0], 64).unwrap();
+        assert_eq!(result.gamma, u64::MAX);
+        assert_eq!(result.epsilon, 0);
+        assert_eq!(result.oxygen, u64::MAX);
+        assert_eq!(result.co2, 0);
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            Diagnostics::from_readings(&[], 5),
+            Err(DiagnosticError::NoReadings)
+        );
+        assert_eq!(
+            Diagnostics::from_readings(&[1], 65),
+            Err(DiagnosticError::TooWide {
+                width: 65,
+                radix: 2
+            })
+        );
+        assert_eq!(
+            Diagnostics::from_readings(&[0b11, 0b100], 2)
+                .unwrap_err()
+                .to_string(),
+            "line 2: expected a reading 2 digits wide but found 3"
+        );
+        assert!(matches!(
+            Diagnostics::from_strings(&["0101", "01"]),
+            Err(DiagnosticError::Reading(_))
+        ));
+        assert_eq!(
+            Diagnostics::from_strings(&["0101", "1100"]).unwrap().gamma,
+            0b0100
+        );
+        assert_eq!(
+            Diagnostics::from_radix_strings(&["12", "21"], 1),
+            Err(DiagnosticError::InvalidRadix(1))
+        );
+    }
+
+    #[test]
+    fn test_read_radix() {
+        let ternary = "012\n112\n\n210\n111\n022\n";
+        assert_eq!(
+            Diagnostics::read_radix(ternary.as_bytes(), 3).unwrap(),
+            Diagnostics::from_radix_strings(&["012", "112", "210", "111", "022"], 3)
+        );
+        let binary = "00100\n11110\n10110\n";
+        assert_eq!(
+            Diagnostics::read_radix(binary.as_bytes(), 2).unwrap(),
+            Diagnostics::from_radix_strings(&["00100", "11110", "10110"], 2)
+        );
+        assert_eq!(
+            Diagnostics::read_radix("12\n123\n".as_bytes(), 4)
+                .unwrap()
+                .unwrap_err()
+                .to_string(),
+            Diagnostics::from_radix_strings(&["12", "123"], 4)
+                .unwrap_err()
+                .to_string()
+        );
+    }
+}
@@ -0,0 +1,126 @@
+use crate::{
+    criteria::{BitCriterion, CriteriaError},
+    readings::Readings,
+};
+
+const NO_CHILD: usize = 0;
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Node {
+    children: [usize; 2],
+    count: usize,
+    first_reading: usize,
+}
+
+// A binary trie over the readings, most significant position first. Every
+// node counts the readings below it, so the ones and zeros at a position among
+// the readings still in the running are the counts of the current node's two
+// children, and a rating is a single walk from the root.
+pub struct ReadingTrie {
+    width: usize,
+    nodes: Vec<Node>,
+}
+
+impl ReadingTrie {
+    pub fn new(readings: &Readings) -> Self {
+        let mut nodes = vec![Node::default()];
+        for index in 0..readings.len() {
+            let mut current = 0;
+            nodes[current].count += 1;
+            for position in 0..readings.width() {
+                let bit = usize::from(readings.bit(index, position));
+                if nodes[current].children[bit] == NO_CHILD {
+                    nodes.push(Node {
+                        first_reading: index,
+                        ..Node::default()
+                    });
+                    nodes[current].children[bit] = nodes.len() - 1;
+                }
+                current = nodes[current].children[bit];
+                nodes[current].count += 1;
+            }
+        }
+        ReadingTrie {
+            width: readings.width(),
+            nodes,
+        }
+    }
+
+    fn count(&self, node: usize) -> usize {
+        if node == NO_CHILD {
+            0
+        } else {
+            self.nodes[node].count
+        }
+    }
+
+    // Walks the same path as `filter_readings` and returns the same index,
+    // including skipping positions where only one child exists.
+    pub fn select(&self, criterion: &impl BitCriterion) -> Result<usize, CriteriaError> {
+        if self.nodes[0].count == 0 {
+            return Err(CriteriaError::NoReadings);
+        }
+        let mut current = 0;
+        for position in 0..self.width {
+            if self.nodes[current].count == 1 {
+                break;
+            }
+            let [zeros, ones] = self.nodes[current].children;
+            current = match (zeros, ones) {
+                (NO_CHILD, only) | (only, NO_CHILD) => only,
+                _ => {
+                    let keep = criterion.select(position, self.count(ones), self.count(zeros))?;
+                    if keep {
+                        ones
+                    } else {
+                        zeros
+                    }
+                }
+            };
+        }
+        Ok(self.nodes[current].first_reading)
+    }
+}
+
+#[cfg(test)]
+mod tests_trie {
+    use super::*;
+    use crate::criteria::{filter_readings, BitCriteria, Commonality, TieBreak};
+
+    #[test]
+    fn test_trie_counts() {
+        let readings = Readings::from_strings(&["101", "100", "011"]);
+        let trie = ReadingTrie::new(&readings);
+        assert_eq!(trie.nodes[0].count, 3);
+        assert_eq!(trie.count(trie.nodes[0].children[1]), 2);
+        assert_eq!(trie.count(trie.nodes[0].children[0]), 1);
+        assert_eq!(trie.nodes.len(), 8);
+    }
+
+    #[test]
+    fn test_trie_select() {
+        let readings = Readings::from_strings(&["101", "100", "011"]);
+        let trie = ReadingTrie::new(&readings);
+        assert_eq!(trie.select(&BitCriteria::oxygen_generator()), Ok(0));
+        assert_eq!(trie.select(&BitCriteria::co2_scrubber()), Ok(2));
+        assert_eq!(
+            ReadingTrie::new(&Readings::new(3)).select(&BitCriteria::gamma()),
+            Err(CriteriaError::NoReadings)
+        );
+    }
+
+    #[test]
+    fn test_trie_matches_filter() {
+        let readings = Readings::from_strings(&["101", "100", "011", "011", "111", "000"]);
+        let trie = ReadingTrie::new(&readings);
+        for commonality in [Commonality::MostCommon, Commonality::LeastCommon] {
+            for tie_break in [TieBreak::Zero, TieBreak::One, TieBreak::Error] {
+                let criteria = BitCriteria::new(commonality, tie_break);
+                assert_eq!(
+                    trie.select(&criteria),
+                    filter_readings(&readings, &criteria)
+                );
+            }
+        }
+    }
+}
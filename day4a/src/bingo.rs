@@ -37,7 +37,7 @@ impl BingoGame {
         let reader = BufReader::new(file);
         let mut file_lines: Vec<String> = Vec::new();
 
-        for (_, line) in reader.lines().enumerate() {
+        for line in reader.lines() {
             let line = line.unwrap();
             file_lines.push(line);
         }
@@ -59,12 +59,12 @@ impl BingoGame {
     }
 
     fn parse_draws(&self, draw_line: String) -> Vec<usize> {
-        return draw_line
+        draw_line
             .split(',')
             .map(|s| s.trim())
             .filter(|s| !s.is_empty())
             .map(|s| s.parse().unwrap())
-            .collect();
+            .collect()
     }
 
     fn parse_bingo_board_line(&self, bingo_board_line: String) -> Vec<BingoCell> {
@@ -161,8 +161,8 @@ impl BingoGame {
                 // Have to draw at least one row/column size to win
                 let winner_found: Option<&BingoBoard> = self.check_boards();
 
-                if let Some(..) = winner_found {
-                    let unmarked_cell_sum: usize = self.unmarked_cell_sum(winner_found.unwrap());
+                if let Some(winning_board) = winner_found {
+                    let unmarked_cell_sum: usize = self.unmarked_cell_sum(winning_board);
                     winning_value = unmarked_cell_sum * number_drawn;
                     break;
                 }
@@ -203,13 +203,13 @@ mod tests_day4a {
             bingo_game.process_draw(number_drawn)
         }
 
-        let bingo_board: &BingoBoard = bingo_game.boards.get(0).unwrap();
+        let bingo_board: &BingoBoard = bingo_game.boards.first().unwrap();
         assert_eq!(bingo_board.len(), BINGO_BOARD_SIZE);
-        assert_eq!(bingo_board[0][3].number_called, true);
-        assert_eq!(bingo_board[1][3].number_called, true);
-        assert_eq!(bingo_board[2][3].number_called, false);
-        assert_eq!(bingo_board[3][3].number_called, false);
-        assert_eq!(bingo_board[4][3].number_called, false);
+        assert!(bingo_board[0][3].number_called);
+        assert!(bingo_board[1][3].number_called);
+        assert!(!bingo_board[2][3].number_called);
+        assert!(!bingo_board[3][3].number_called);
+        assert!(!bingo_board[4][3].number_called);
     }
 
     #[test]
@@ -223,7 +223,7 @@ mod tests_day4a {
         }
 
         let winner_found: Option<&BingoBoard> = bingo_game.check_boards();
-        assert_eq!(winner_found.is_none(), true);
+        assert!(winner_found.is_none());
 
         for i in 2..12 {
             // Should win on the 12th draw
@@ -232,7 +232,7 @@ mod tests_day4a {
         }
 
         let winner_found: Option<&BingoBoard> = bingo_game.check_boards();
-        assert_eq!(winner_found.is_some(), true);
+        assert!(winner_found.is_some());
     }
 
     #[test]
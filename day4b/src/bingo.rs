@@ -1,21 +1,118 @@
+mod board;
+mod index;
+mod pattern;
+mod timeline;
+
+pub use pattern::{Cell, PatternError, WinPattern};
+pub use timeline::{BingoTimeline, BoardWin};
+
+use board::{BingoBoards, WinMasks};
+use index::DrawIndex;
+
 use std::{
+    error, fmt,
     fs::File,
-    io::{prelude::*, BufReader},
+    io::{self, prelude::*, BufReader},
     path::Path,
 };
 
-const BINGO_BOARD_SIZE: usize = 5;
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BoardSize {
+    pub rows: usize,
+    pub columns: usize,
+}
+
+impl fmt::Display for BoardSize {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}x{}", self.rows, self.columns)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BingoError {
+    NoDraws,
+    InvalidNumber {
+        line_number: usize,
+        value: String,
+    },
+    RaggedBoard {
+        line_number: usize,
+        expected: usize,
+        found: usize,
+    },
+    MismatchedBoardSize {
+        board: usize,
+        expected: BoardSize,
+        found: BoardSize,
+    },
+    Pattern(PatternError),
+    // `io::Error` is neither `Clone` nor `Eq`, so only its kind and message
+    // are kept.
+    Io {
+        kind: io::ErrorKind,
+        message: String,
+    },
+}
+
+impl fmt::Display for BingoError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BingoError::NoDraws => write!(f, "the input has no draws"),
+            BingoError::InvalidNumber { line_number, value } => {
+                write!(f, "line {}: invalid number {:?}", line_number, value)
+            }
+            BingoError::RaggedBoard {
+                line_number,
+                expected,
+                found,
+            } => write!(
+                f,
+                "line {}: expected a row of {} numbers but found {}",
+                line_number, expected, found
+            ),
+            BingoError::MismatchedBoardSize {
+                board,
+                expected,
+                found,
+            } => write!(
+                f,
+                "board {} is {} but boards are {}",
+                board, found, expected
+            ),
+            BingoError::Pattern(error) => write!(f, "{}", error),
+            BingoError::Io { message, .. } => write!(f, "{}", message),
+        }
+    }
+}
+
+impl error::Error for BingoError {}
 
-pub struct BingoCell {
-    number_in_cell: usize,
-    number_called: bool,
+impl From<io::Error> for BingoError {
+    fn from(error: io::Error) -> Self {
+        BingoError::Io {
+            kind: error.kind(),
+            message: error.to_string(),
+        }
+    }
 }
 
-type BingoBoard = Vec<Vec<BingoCell>>;
+impl From<PatternError> for BingoError {
+    fn from(error: PatternError) -> Self {
+        BingoError::Pattern(error)
+    }
+}
+
+type BingoBoard = Vec<Vec<usize>>;
 
 pub struct BingoGame {
     draws: Vec<usize>,
-    boards: Vec<BingoBoard>,
+    boards: BingoBoards,
+    board_size: Option<BoardSize>,
+    win_patterns: Vec<WinPattern>,
+    draw_index: DrawIndex,
+    // Built from the patterns once the board size is known.
+    win_masks: WinMasks,
+    has_won: Vec<bool>,
 }
 
 impl Default for BingoGame {
@@ -25,154 +122,243 @@ impl Default for BingoGame {
 }
 
 impl BingoGame {
+    // The board size is inferred from the first board read.
     pub fn new() -> Self {
         BingoGame {
             draws: Vec::new(),
-            boards: Vec::new(),
+            boards: BingoBoards::default(),
+            board_size: None,
+            win_patterns: WinPattern::standard(),
+            draw_index: DrawIndex::default(),
+            win_masks: WinMasks::default(),
+            has_won: Vec::new(),
         }
     }
 
-    pub fn read_lines_from_input_file(&mut self, filename: impl AsRef<Path>) {
-        let file = File::open(filename).expect("no such file");
-        let reader = BufReader::new(file);
-        let mut file_lines: Vec<String> = Vec::new();
+    pub fn with_board_size(rows: usize, columns: usize) -> Self {
+        let mut bingo_game = Self::new();
+        bingo_game.set_board_size(BoardSize { rows, columns });
+        bingo_game
+            .update_winning_groups()
+            .expect("rows and columns fit any board");
+        bingo_game
+    }
 
-        for (_, line) in reader.lines().enumerate() {
-            let line = line.unwrap();
-            file_lines.push(line);
-        }
-        self.draws = self.parse_draws(file_lines[0].to_string());
-        file_lines.drain(0..1);
+    pub fn board_size(&self) -> Option<BoardSize> {
+        self.board_size
+    }
 
-        while !file_lines.is_empty() {
-            file_lines.drain(0..1); // Drain new line before board
-            let mut new_board: Vec<Vec<BingoCell>> = Vec::new();
+    pub fn win_patterns(&self) -> &[WinPattern] {
+        &self.win_patterns
+    }
 
-            // For each of 5 rows create a new board row
-            for line in file_lines.iter().take(5) {
-                let new_row: Vec<BingoCell> = self.parse_bingo_board_line(line.to_string());
-                new_board.push(new_row);
-            }
-            file_lines.drain(0..5); // Remove 6 lines
-            self.boards.push(new_board);
+    // Patterns are checked against the board size now if it is known, or
+    // when the first board is read otherwise.
+    pub fn set_win_patterns(&mut self, win_patterns: Vec<WinPattern>) -> Result<(), BingoError> {
+        let previous = std::mem::replace(&mut self.win_patterns, win_patterns);
+        if let Err(error) = self.update_winning_groups() {
+            self.win_patterns = previous;
+            return Err(error);
         }
+        Ok(())
     }
 
-    fn parse_draws(&self, draw_line: String) -> Vec<usize> {
-        return draw_line
-            .split(',')
-            .map(|s| s.trim())
-            .filter(|s| !s.is_empty())
-            .map(|s| s.parse().unwrap())
-            .collect();
+    // Only called before any board is added.
+    fn set_board_size(&mut self, size: BoardSize) {
+        self.board_size = Some(size);
+        self.boards = BingoBoards::new(size);
     }
 
-    fn parse_bingo_board_line(&self, bingo_board_line: String) -> Vec<BingoCell> {
-        let mut return_vector: Vec<BingoCell> = Vec::new();
-        let splits = bingo_board_line.split_whitespace();
-        for split in splits {
-            let parsed_value: usize = split.parse().unwrap();
-            let bingo_cell = BingoCell {
-                number_in_cell: parsed_value,
-                number_called: false,
-            };
-            return_vector.push(bingo_cell);
-        }
-        return_vector
-    }
-
-    fn process_draw(&mut self, number_drawn: usize) {
-        for bingo_board in self.boards.iter_mut() {
-            for bingo_board_row in bingo_board.iter_mut() {
-                for bingo_board_cell in bingo_board_row.iter_mut() {
-                    if bingo_board_cell.number_in_cell == number_drawn {
-                        bingo_board_cell.number_called = true;
-                    }
-                }
+    // Rebuilds the win masks for the current patterns and rechecks the marks
+    // already on the boards.
+    fn update_winning_groups(&mut self) -> Result<(), BingoError> {
+        if let Some(size) = self.board_size {
+            let mut winning_groups = Vec::new();
+            for win_pattern in &self.win_patterns {
+                winning_groups.extend(win_pattern.cell_groups(size)?);
             }
+            self.win_masks = WinMasks::new(size, &winning_groups);
+            self.has_won = (0..self.boards.len())
+                .map(|board| self.win_masks.any_complete(self.boards.marked(board)))
+                .collect();
         }
+        Ok(())
     }
 
-    pub fn get_winning_board_positions(&self) -> Vec<usize> {
-        let mut board_positions: Vec<usize> = Vec::new();
-        for (board_position, board) in self.boards.iter().enumerate() {
-            let row_win = self.check_board_rows(board);
-            let column_win = self.check_board_columns(board);
-            if row_win || column_win {
-                board_positions.push(board_position);
-            }
-        }
-        board_positions
+    pub fn read_lines_from_input_file(
+        &mut self,
+        filename: impl AsRef<Path>,
+    ) -> Result<(), BingoError> {
+        let file = File::open(filename)?;
+        let reader = BufReader::new(file);
+        let file_lines = reader.lines().collect::<Result<Vec<String>, _>>()?;
+        self.parse_lines(&file_lines)
     }
 
-    fn check_board_rows(&self, bingo_board: &[Vec<BingoCell>]) -> bool {
-        for row in bingo_board {
-            let mut row_count: usize = 0;
-            for cell in row {
-                if cell.number_called {
-                    row_count += 1;
-                }
-            }
-            if row_count == BINGO_BOARD_SIZE {
-                return true;
+    // The first non-blank line holds the draws; boards follow, separated by
+    // blank lines. Every board must have the same number of rows and every row
+    // the same number of columns.
+    pub fn parse_lines<S: AsRef<str>>(&mut self, file_lines: &[S]) -> Result<(), BingoError> {
+        let mut lines = file_lines
+            .iter()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line.as_ref().trim()));
+        let (line_number, draw_line) = lines
+            .find(|(_, line)| !line.is_empty())
+            .ok_or(BingoError::NoDraws)?;
+        self.draws = self.parse_draws(line_number, draw_line)?;
+
+        let mut new_board: BingoBoard = Vec::new();
+        for (line_number, line) in lines.chain([(0, "")]) {
+            if !line.is_empty() {
+                new_board.push(self.parse_bingo_board_line(line_number, line, &new_board)?);
+            } else if !new_board.is_empty() {
+                self.add_board(std::mem::take(&mut new_board))?;
             }
         }
-        false
+        Ok(())
     }
 
-    fn check_board_columns(&self, bingo_board: &[Vec<BingoCell>]) -> bool {
-        let mut column_counts: Vec<usize> = vec![0; bingo_board.len()];
-        for i in 0..bingo_board.len() {
-            for cell in bingo_board.iter().take(bingo_board[i].len()) {
-                if cell[i].number_called {
-                    column_counts[i] += 1;
-                }
+    fn add_board(&mut self, new_board: BingoBoard) -> Result<(), BingoError> {
+        let found = BoardSize {
+            rows: new_board.len(),
+            columns: new_board[0].len(),
+        };
+        let expected = match self.board_size {
+            Some(expected) => expected,
+            None => {
+                self.set_board_size(found);
+                self.update_winning_groups()?;
+                found
             }
+        };
+        if found != expected {
+            return Err(BingoError::MismatchedBoardSize {
+                board: self.boards.len(),
+                expected,
+                found,
+            });
         }
+        let board_position = self.boards.len();
+        for (cell, &number) in new_board.iter().flatten().enumerate() {
+            self.draw_index.insert(number, board_position, cell);
+        }
+        self.boards.push(new_board.into_iter().flatten());
+        self.has_won.push(false);
+        Ok(())
+    }
+
+    fn parse_number(&self, line_number: usize, value: &str) -> Result<usize, BingoError> {
+        value.parse().map_err(|_| BingoError::InvalidNumber {
+            line_number,
+            value: value.to_string(),
+        })
+    }
+
+    fn parse_draws(&self, line_number: usize, draw_line: &str) -> Result<Vec<usize>, BingoError> {
+        draw_line
+            .split(',')
+            .map(|s| s.trim())
+            .filter(|s| !s.is_empty())
+            .map(|s| self.parse_number(line_number, s))
+            .collect()
+    }
 
-        for column_count in &column_counts {
-            if column_count == &BINGO_BOARD_SIZE {
-                return true;
+    fn parse_bingo_board_line(
+        &self,
+        line_number: usize,
+        bingo_board_line: &str,
+        board_so_far: &[Vec<usize>],
+    ) -> Result<Vec<usize>, BingoError> {
+        let mut return_vector: Vec<usize> = Vec::new();
+        for split in bingo_board_line.split_whitespace() {
+            return_vector.push(self.parse_number(line_number, split)?);
+        }
+        if let Some(first_row) = board_so_far.first() {
+            if return_vector.len() != first_row.len() {
+                return Err(BingoError::RaggedBoard {
+                    line_number,
+                    expected: first_row.len(),
+                    found: return_vector.len(),
+                });
             }
         }
-        false
+        Ok(return_vector)
     }
 
-    pub fn unmarked_cell_sum(&self, bingo_board: &[Vec<BingoCell>]) -> usize {
-        let mut unmarked_sum: usize = 0;
-        for row in bingo_board {
-            for cell in row {
-                if !cell.number_called {
-                    unmarked_sum += cell.number_in_cell;
-                }
+    // Marks the number wherever it appears and returns the boards it made
+    // win for the first time, in board order.
+    fn process_draw(&mut self, number_drawn: usize) -> Vec<usize> {
+        let mut new_winners = Vec::new();
+        for &(board_position, cell) in self.draw_index.occurrences(number_drawn) {
+            if !self.boards.mark(board_position, cell) || self.has_won[board_position] {
+                continue;
+            }
+            if self
+                .win_masks
+                .completes(self.boards.marked(board_position), cell)
+            {
+                self.has_won[board_position] = true;
+                new_winners.push(board_position);
             }
         }
-        unmarked_sum
+        new_winners
     }
 
-    pub fn play_until_last_winner(&mut self) -> usize {
-        let mut last_winning_value: usize = 0;
-
-        for draw_position in 0..self.draws.len() {
-            let number_drawn: usize = self.draws[draw_position];
-            self.process_draw(number_drawn);
-
-            if draw_position > BINGO_BOARD_SIZE {
-                let mut round_winning_board_positions = self.get_winning_board_positions();
-                if !round_winning_board_positions.is_empty() {
-                    let last_index: usize = *round_winning_board_positions.last().unwrap();
-                    let unmarked_cell_sum: usize =
-                        self.unmarked_cell_sum(self.boards.get(last_index).unwrap());
-                    last_winning_value = unmarked_cell_sum * number_drawn;
-                    round_winning_board_positions.reverse();
-                    for round_winning_board_position in &round_winning_board_positions {
-                        self.boards.remove(*round_winning_board_position);
-                    }
-                }
+    pub fn get_winning_board_positions(&self) -> Vec<usize> {
+        (0..self.boards.len())
+            .filter(|&board_position| self.has_won[board_position])
+            .collect()
+    }
+
+    pub fn is_marked(&self, board_position: usize, row: usize, column: usize) -> bool {
+        let cell = self.boards.cell_index((row, column));
+        self.boards.is_marked(board_position, cell)
+    }
+
+    pub fn unmarked_cell_sum(&self, board_position: usize) -> usize {
+        self.boards.unmarked_sum(board_position)
+    }
+
+    fn reset_marks(&mut self) {
+        self.boards.clear_marks();
+        self.has_won.fill(false);
+    }
+
+    // Plays every draw from fresh boards and records when each board wins.
+    // Boards keep their marks afterwards, so a board's score is only
+    // meaningful through the returned timeline.
+    pub fn play(&mut self) -> BingoTimeline {
+        self.reset_marks();
+        let mut timeline = BingoTimeline::default();
+
+        for draw_index in 0..self.draws.len() {
+            if timeline.wins.len() == self.boards.len() {
+                break;
+            }
+            let number_drawn: usize = self.draws[draw_index];
+            for board in self.process_draw(number_drawn) {
+                timeline.wins.push(BoardWin {
+                    board,
+                    draw_index,
+                    number: number_drawn,
+                    score: self.unmarked_cell_sum(board) * number_drawn,
+                });
             }
         }
 
-        last_winning_value
+        timeline.never_won = (0..self.boards.len())
+            .filter(|&board| !self.has_won[board])
+            .collect();
+        timeline
+    }
+
+    pub fn play_until_winner(&mut self) -> usize {
+        self.play().first_score()
+    }
+
+    pub fn play_until_last_winner(&mut self) -> usize {
+        self.play().last_score()
     }
 }
 
@@ -183,7 +369,9 @@ mod tests_day4a {
     #[test]
     fn test_read_draws_from_input_file() {
         let mut bingo_game = BingoGame::new();
-        bingo_game.read_lines_from_input_file("sample_input.txt");
+        bingo_game
+            .read_lines_from_input_file("sample_input.txt")
+            .unwrap();
 
         assert_eq!(bingo_game.draws.len(), 27);
     }
@@ -191,7 +379,9 @@ mod tests_day4a {
     #[test]
     fn test_read_boards_from_input_file() {
         let mut bingo_game = BingoGame::new();
-        bingo_game.read_lines_from_input_file("sample_input.txt");
+        bingo_game
+            .read_lines_from_input_file("sample_input.txt")
+            .unwrap();
 
         assert_eq!(bingo_game.boards.len(), 3);
     }
@@ -199,28 +389,281 @@ mod tests_day4a {
     #[test]
     fn test_mark_bingo_cells_from_draws() {
         let mut bingo_game = BingoGame::new();
-        bingo_game.read_lines_from_input_file("sample_input.txt");
+        bingo_game
+            .read_lines_from_input_file("sample_input.txt")
+            .unwrap();
 
         for i in 0..5 {
             let number_drawn: usize = bingo_game.draws[i];
-            bingo_game.process_draw(number_drawn)
+            bingo_game.process_draw(number_drawn);
         }
 
-        let bingo_board: &BingoBoard = bingo_game.boards.get(0).unwrap();
-        assert_eq!(bingo_board.len(), BINGO_BOARD_SIZE);
-        assert_eq!(bingo_board[0][3].number_called, true);
-        assert_eq!(bingo_board[1][3].number_called, true);
-        assert_eq!(bingo_board[2][3].number_called, false);
-        assert_eq!(bingo_board[3][3].number_called, false);
-        assert_eq!(bingo_board[4][3].number_called, false);
+        assert_eq!(bingo_game.board_size().map(|size| size.rows), Some(5));
+        assert!(bingo_game.is_marked(0, 0, 3));
+        assert!(bingo_game.is_marked(0, 1, 3));
+        assert!(!bingo_game.is_marked(0, 2, 3));
+        assert!(!bingo_game.is_marked(0, 3, 3));
+        assert!(!bingo_game.is_marked(0, 4, 3));
     }
 
     #[test]
     fn test_run_game() {
         let mut bingo_game = BingoGame::new();
-        bingo_game.read_lines_from_input_file("sample_input.txt");
+        bingo_game
+            .read_lines_from_input_file("sample_input.txt")
+            .unwrap();
 
         let winning_value: usize = bingo_game.play_until_last_winner();
         assert_eq!(winning_value, 1924);
     }
+
+    #[test]
+    fn test_infer_board_size() {
+        let mut bingo_game = BingoGame::new();
+        bingo_game
+            .read_lines_from_input_file("sample_input.txt")
+            .unwrap();
+        assert_eq!(
+            bingo_game.board_size(),
+            Some(BoardSize {
+                rows: 5,
+                columns: 5
+            })
+        );
+    }
+
+    #[test]
+    fn test_rectangular_boards() {
+        let mut bingo_game = BingoGame::new();
+        bingo_game
+            .parse_lines(&[
+                "1,2,3,4,5,6",
+                "",
+                "1 2 3 4",
+                "5 6 7 8",
+                "9 10 11 12",
+                "",
+                "",
+                "4 3 9 1",
+                "8 7 6 5",
+                "12 11 10 2",
+            ])
+            .unwrap();
+        assert_eq!(bingo_game.boards.len(), 2);
+        assert_eq!(
+            bingo_game.board_size(),
+            Some(BoardSize {
+                rows: 3,
+                columns: 4
+            })
+        );
+        // The first board's top row completes on 4 and the second board's last
+        // column on 5, leaving 6 to 12 unmarked.
+        assert_eq!(bingo_game.play_until_last_winner(), 63 * 5);
+    }
+
+    #[test]
+    fn test_board_size_errors() {
+        let mut bingo_game = BingoGame::with_board_size(2, 2);
+        assert_eq!(
+            bingo_game.parse_lines(&["1,2", "", "1 2", "3 4", "", "1 2 3", "4 5 6"]),
+            Err(BingoError::MismatchedBoardSize {
+                board: 1,
+                expected: BoardSize {
+                    rows: 2,
+                    columns: 2
+                },
+                found: BoardSize {
+                    rows: 2,
+                    columns: 3
+                },
+            })
+        );
+        assert_eq!(
+            BingoGame::new()
+                .parse_lines(&["1,2", "", "1 2", "3"])
+                .unwrap_err()
+                .to_string(),
+            "line 4: expected a row of 2 numbers but found 1"
+        );
+        assert_eq!(
+            BingoGame::new().parse_lines(&["1,x"]),
+            Err(BingoError::InvalidNumber {
+                line_number: 1,
+                value: "x".to_string()
+            })
+        );
+        assert_eq!(
+            BingoGame::new().parse_lines(&["", ""]),
+            Err(BingoError::NoDraws)
+        );
+    }
+
+    #[test]
+    fn test_win_patterns() {
+        let lines = [
+            "5,1,9,3,7",
+            "",
+            "1 2 3",
+            "4 5 6",
+            "7 8 9",
+            "",
+            "9 8 7",
+            "6 5 4",
+            "3 2 1",
+        ];
+        let mut bingo_game = BingoGame::new();
+        bingo_game
+            .set_win_patterns(vec![WinPattern::FourCorners])
+            .unwrap();
+        bingo_game.parse_lines(&lines).unwrap();
+        // Both boards complete their corners on the last draw.
+        assert_eq!(bingo_game.play_until_last_winner(), (2 + 4 + 6 + 8) * 7);
+
+        let mut bingo_game = BingoGame::new();
+        bingo_game.parse_lines(&lines).unwrap();
+        bingo_game
+            .set_win_patterns(vec![WinPattern::parse_mask("X . .\n. X .\n. . .").unwrap()])
+            .unwrap();
+        for draw in [5, 1] {
+            bingo_game.process_draw(draw);
+        }
+        assert_eq!(bingo_game.get_winning_board_positions(), vec![0]);
+    }
+
+    #[test]
+    fn test_invalid_win_patterns() {
+        let mut bingo_game = BingoGame::with_board_size(5, 5);
+        let mask = WinPattern::parse_mask("X.\n.X").unwrap();
+        assert!(matches!(
+            bingo_game.set_win_patterns(vec![WinPattern::X, mask]),
+            Err(BingoError::Pattern(PatternError::MaskSizeMismatch { .. }))
+        ));
+        assert_eq!(bingo_game.win_patterns(), WinPattern::standard());
+
+        let mut bingo_game = BingoGame::new();
+        bingo_game.set_win_patterns(vec![WinPattern::X]).unwrap();
+        assert!(matches!(
+            bingo_game.parse_lines(&["1", "", "1 2"]),
+            Err(BingoError::Pattern(PatternError::NotSquare { .. }))
+        ));
+    }
+
+    #[test]
+    fn test_play_timeline() {
+        let mut bingo_game = BingoGame::new();
+        bingo_game
+            .read_lines_from_input_file("sample_input.txt")
+            .unwrap();
+
+        let timeline = bingo_game.play();
+        assert_eq!(
+            timeline.wins,
+            vec![
+                BoardWin {
+                    board: 2,
+                    draw_index: 11,
+                    number: 24,
+                    score: 4512
+                },
+                BoardWin {
+                    board: 0,
+                    draw_index: 13,
+                    number: 16,
+                    score: 2192
+                },
+                BoardWin {
+                    board: 1,
+                    draw_index: 14,
+                    number: 13,
+                    score: 1924
+                },
+            ]
+        );
+        assert!(timeline.never_won.is_empty());
+        assert_eq!(timeline.win_of(0).map(|win| win.number), Some(16));
+        assert_eq!(bingo_game.play_until_winner(), 4512);
+        assert_eq!(bingo_game.play(), timeline);
+    }
+
+    #[test]
+    fn test_boards_that_never_win() {
+        let mut bingo_game = BingoGame::new();
+        bingo_game
+            .parse_lines(&[
+                "1,2,3", "", "1 2", "3 4", "", "5 6", "7 8", "", "2 9", "3 1",
+            ])
+            .unwrap();
+        let timeline = bingo_game.play();
+        assert_eq!(timeline.wins.len(), 2);
+        assert_eq!((timeline.wins[0].board, timeline.wins[1].board), (0, 2));
+        assert_eq!(timeline.wins[0].draw_index, 1);
+        assert_eq!(timeline.never_won, vec![1]);
+        assert_eq!(timeline.last_score(), 9 * 3);
+        assert_eq!(BingoTimeline::default().first_score(), 0);
+    }
+
+    #[test]
+    fn test_many_boards() {
+        // Board i holds i * 9 + 1 to i * 9 + 9, and the draws call each board's
+        // middle row in turn, so boards win in order on every third draw.
+        let boards = 10_000;
+        let mut lines = vec![(0..boards)
+            .flat_map(|board| (4..=6).map(move |offset| (board * 9 + offset).to_string()))
+            .collect::<Vec<_>>()
+            .join(",")];
+        for board in 0..boards {
+            lines.push(String::new());
+            for row in 0..3 {
+                let numbers: Vec<String> = (1..=3)
+                    .map(|column| (board * 9 + row * 3 + column).to_string())
+                    .collect();
+                lines.push(numbers.join(" "));
+            }
+        }
+
+        let mut bingo_game = BingoGame::new();
+        bingo_game.parse_lines(&lines).unwrap();
+        let timeline = bingo_game.play();
+        assert_eq!(timeline.wins.len(), boards);
+        let last_board = boards - 1;
+        assert_eq!(
+            timeline.last_winner(),
+            Some(&BoardWin {
+                board: last_board,
+                draw_index: boards * 3 - 1,
+                number: last_board * 9 + 6,
+                score: (last_board * 54 + 30) * (last_board * 9 + 6),
+            })
+        );
+    }
+
+    #[test]
+    fn test_patterns_changed_mid_game() {
+        let mut bingo_game = BingoGame::new();
+        bingo_game
+            .parse_lines(&["1,5,9", "", "1 2 3", "4 5 6", "7 8 9"])
+            .unwrap();
+        for draw in [1, 5, 9] {
+            bingo_game.process_draw(draw);
+        }
+        assert!(bingo_game.get_winning_board_positions().is_empty());
+        bingo_game
+            .set_win_patterns(vec![WinPattern::Diagonals])
+            .unwrap();
+        assert_eq!(bingo_game.get_winning_board_positions(), vec![0]);
+    }
+
+    #[test]
+    fn test_missing_input_file() {
+        let mut bingo_game = BingoGame::new();
+        assert!(matches!(
+            bingo_game.read_lines_from_input_file("no_such_input.txt"),
+            Err(BingoError::Io {
+                kind: io::ErrorKind::NotFound,
+                ..
+            })
+        ));
+        assert!(bingo_game.draws.is_empty());
+    }
 }
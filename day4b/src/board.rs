@@ -0,0 +1,201 @@
+use crate::{BoardSize, Cell};
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+fn words_for(cells: usize) -> usize {
+    cells.div_ceil(WORD_BITS).max(1)
+}
+
+// Every board's numbers row-major in one vector, and every board's marked
+// cells as a bitmask of `words_per_board` words. Cell `row * columns +
+// column` is bit `cell % 64` of word `cell / 64`, so a 5x5 board is marked
+// in a single word.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct BingoBoards {
+    size: Option<BoardSize>,
+    words_per_board: usize,
+    numbers: Vec<usize>,
+    marked: Vec<u64>,
+}
+
+impl BingoBoards {
+    pub(crate) fn new(size: BoardSize) -> Self {
+        BingoBoards {
+            size: Some(size),
+            words_per_board: words_for(size.rows * size.columns),
+            numbers: Vec::new(),
+            marked: Vec::new(),
+        }
+    }
+
+    fn cells_per_board(&self) -> usize {
+        self.size.map_or(0, |size| size.rows * size.columns)
+    }
+
+    pub(crate) fn cell_index(&self, (row, column): Cell) -> usize {
+        row * self.size.map_or(0, |size| size.columns) + column
+    }
+
+    pub(crate) fn push(&mut self, numbers: impl IntoIterator<Item = usize>) {
+        let start = self.numbers.len();
+        self.numbers.extend(numbers);
+        debug_assert_eq!(self.numbers.len() - start, self.cells_per_board());
+        self.marked
+            .resize(self.marked.len() + self.words_per_board, 0);
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.numbers
+            .len()
+            .checked_div(self.cells_per_board())
+            .unwrap_or(0)
+    }
+
+    pub(crate) fn numbers(&self, board: usize) -> &[usize] {
+        let cells = self.cells_per_board();
+        &self.numbers[board * cells..(board + 1) * cells]
+    }
+
+    pub(crate) fn marked(&self, board: usize) -> &[u64] {
+        let start = board * self.words_per_board;
+        &self.marked[start..start + self.words_per_board]
+    }
+
+    pub(crate) fn is_marked(&self, board: usize, cell: usize) -> bool {
+        self.marked(board)[cell / WORD_BITS] >> (cell % WORD_BITS) & 1 == 1
+    }
+
+    // Returns whether the cell was not marked before.
+    pub(crate) fn mark(&mut self, board: usize, cell: usize) -> bool {
+        let word = &mut self.marked[board * self.words_per_board + cell / WORD_BITS];
+        let bit = 1 << (cell % WORD_BITS);
+        let newly_marked = *word & bit == 0;
+        *word |= bit;
+        newly_marked
+    }
+
+    pub(crate) fn clear_marks(&mut self) {
+        self.marked.fill(0);
+    }
+
+    pub(crate) fn unmarked_sum(&self, board: usize) -> usize {
+        self.numbers(board)
+            .iter()
+            .enumerate()
+            .filter(|&(cell, _)| !self.is_marked(board, cell))
+            .map(|(_, &number)| number)
+            .sum()
+    }
+}
+
+// One bitmask per winning cell group, laid out like a board's marks, and
+// the groups each cell belongs to. A group is complete when the board's
+// marks cover its mask.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct WinMasks {
+    words_per_board: usize,
+    masks: Vec<u64>,
+    groups_of_cell: Vec<Vec<usize>>,
+}
+
+impl WinMasks {
+    pub(crate) fn new(size: BoardSize, groups: &[Vec<Cell>]) -> Self {
+        let words_per_board = words_for(size.rows * size.columns);
+        let mut masks = vec![0; groups.len() * words_per_board];
+        let mut groups_of_cell = vec![Vec::new(); size.rows * size.columns];
+        for (group, cells) in groups.iter().enumerate() {
+            for &(row, column) in cells {
+                let cell = row * size.columns + column;
+                masks[group * words_per_board + cell / WORD_BITS] |= 1 << (cell % WORD_BITS);
+                groups_of_cell[cell].push(group);
+            }
+        }
+        WinMasks {
+            words_per_board,
+            masks,
+            groups_of_cell,
+        }
+    }
+
+    fn is_complete(&self, group: usize, marked: &[u64]) -> bool {
+        let start = group * self.words_per_board;
+        self.masks[start..start + self.words_per_board]
+            .iter()
+            .zip(marked)
+            .all(|(&mask, &marks)| marks & mask == mask)
+    }
+
+    // Only the groups containing the newly marked cell can have completed.
+    pub(crate) fn completes(&self, marked: &[u64], cell: usize) -> bool {
+        self.groups_of_cell[cell]
+            .iter()
+            .any(|&group| self.is_complete(group, marked))
+    }
+
+    pub(crate) fn any_complete(&self, marked: &[u64]) -> bool {
+        (0..self.masks.len() / self.words_per_board.max(1))
+            .any(|group| self.is_complete(group, marked))
+    }
+}
+
+#[cfg(test)]
+mod tests_board {
+    use super::*;
+
+    #[test]
+    fn test_bingo_boards() {
+        let mut boards = BingoBoards::new(BoardSize {
+            rows: 2,
+            columns: 3,
+        });
+        boards.push([1, 2, 3, 4, 5, 6]);
+        boards.push([7, 8, 9, 10, 11, 12]);
+        assert_eq!(boards.len(), 2);
+        assert_eq!(boards.numbers(1), &[7, 8, 9, 10, 11, 12]);
+        assert_eq!(boards.cell_index((1, 2)), 5);
+
+        assert!(boards.mark(1, 5));
+        assert!(!boards.mark(1, 5));
+        assert!(boards.mark(1, 0));
+        assert_eq!(boards.marked(1), &[0b100001]);
+        assert_eq!(boards.marked(0), &[0]);
+        assert_eq!(boards.unmarked_sum(1), 8 + 9 + 10 + 11);
+
+        boards.clear_marks();
+        assert!(!boards.is_marked(1, 5));
+        assert_eq!(BingoBoards::default().len(), 0);
+    }
+
+    #[test]
+    fn test_win_masks() {
+        let size = BoardSize {
+            rows: 2,
+            columns: 2,
+        };
+        let win_masks = WinMasks::new(size, &[vec![(0, 0), (0, 1)], vec![(0, 0), (1, 0)]]);
+        assert_eq!(win_masks.masks, vec![0b0011, 0b0101]);
+        assert!(win_masks.completes(&[0b0101], 2));
+        assert!(!win_masks.completes(&[0b0101], 3));
+        assert!(win_masks.any_complete(&[0b1011]));
+        assert!(!win_masks.any_complete(&[0b1001]));
+    }
+
+    #[test]
+    fn test_wide_boards() {
+        let size = BoardSize {
+            rows: 10,
+            columns: 10,
+        };
+        let mut boards = BingoBoards::new(size);
+        boards.push(0..100);
+        let last_row: Vec<Cell> = (0..10).map(|column| (9, column)).collect();
+        let win_masks = WinMasks::new(size, &[last_row]);
+        for cell in 90..99 {
+            boards.mark(0, cell);
+            assert!(!win_masks.completes(boards.marked(0), cell));
+        }
+        boards.mark(0, 99);
+        assert!(win_masks.completes(boards.marked(0), 99));
+        assert_eq!(boards.marked(0).len(), 2);
+    }
+}
@@ -0,0 +1,34 @@
+use std::collections::HashMap;
+
+// Where every number appears: its board and cell index, in board order.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct DrawIndex {
+    occurrences: HashMap<usize, Vec<(usize, usize)>>,
+}
+
+impl DrawIndex {
+    pub(crate) fn insert(&mut self, number: usize, board: usize, cell: usize) {
+        self.occurrences
+            .entry(number)
+            .or_default()
+            .push((board, cell));
+    }
+
+    pub(crate) fn occurrences(&self, number: usize) -> &[(usize, usize)] {
+        self.occurrences.get(&number).map_or(&[], Vec::as_slice)
+    }
+}
+
+#[cfg(test)]
+mod tests_index {
+    use super::*;
+
+    #[test]
+    fn test_draw_index() {
+        let mut draw_index = DrawIndex::default();
+        draw_index.insert(7, 0, 5);
+        draw_index.insert(7, 3, 0);
+        assert_eq!(draw_index.occurrences(7), &[(0, 5), (3, 0)]);
+        assert!(draw_index.occurrences(8).is_empty());
+    }
+}
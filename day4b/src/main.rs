@@ -1,9 +1,49 @@
-use ::cmilbert_aoc_day4b::BingoGame;
+use ::cmilbert_aoc_day4b::{BingoError, BingoGame, WinPattern};
 
-fn main() {
+use std::{env, fs, process};
+
+fn main() -> Result<(), BingoError> {
     let mut bingo_game = BingoGame::new();
-    bingo_game.read_lines_from_input_file("input.txt");
+    let mut print_timeline = false;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--patterns" => {
+                let names = args.next().unwrap_or_default();
+                let win_patterns = names
+                    .split(',')
+                    .map(str::parse)
+                    .collect::<Result<Vec<WinPattern>, _>>()?;
+                bingo_game.set_win_patterns(win_patterns)?;
+            }
+            "--mask" => {
+                let filename = args.next().unwrap_or_default();
+                let mask = fs::read_to_string(filename)?;
+                bingo_game.set_win_patterns(vec![WinPattern::parse_mask(&mask)?])?;
+            }
+            "--timeline" => print_timeline = true,
+            _ => {
+                eprintln!("unknown option {:?}", arg);
+                eprintln!("usage: [--patterns <name,...>] [--mask <file>] [--timeline]");
+                process::exit(1);
+            }
+        }
+    }
+    bingo_game.read_lines_from_input_file("input.txt")?;
 
-    let winning_value: usize = bingo_game.play_until_last_winner();
-    println!("Winning value: {}", winning_value);
+    let timeline = bingo_game.play();
+    if print_timeline {
+        for win in &timeline.wins {
+            println!(
+                "Board {} won on draw {} ({}) with score {}",
+                win.board, win.draw_index, win.number, win.score
+            );
+        }
+        for board in &timeline.never_won {
+            println!("Board {} never won", board);
+        }
+    }
+    println!("First winning value: {}", timeline.first_score());
+    println!("Winning value: {}", timeline.last_score());
+    Ok(())
 }
@@ -0,0 +1,295 @@
+use crate::BoardSize;
+use std::{error, fmt, str::FromStr};
+
+pub type Cell = (usize, usize);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PatternError {
+    UnknownPattern(String),
+    NotSquare { pattern: String, size: BoardSize },
+    MaskSizeMismatch { mask: BoardSize, board: BoardSize },
+    InvalidMaskCharacter { line_number: usize, character: char },
+    RaggedMask { line_number: usize },
+    EmptyMask,
+}
+
+impl fmt::Display for PatternError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PatternError::UnknownPattern(name) => write!(f, "unknown win pattern {:?}", name),
+            PatternError::NotSquare { pattern, size } => {
+                write!(f, "{} need a square board but boards are {}", pattern, size)
+            }
+            PatternError::MaskSizeMismatch { mask, board } => {
+                write!(f, "mask is {} but boards are {}", mask, board)
+            }
+            PatternError::InvalidMaskCharacter {
+                line_number,
+                character,
+            } => write!(
+                f,
+                "line {}: invalid mask character {:?}",
+                line_number, character
+            ),
+            PatternError::RaggedMask { line_number } => {
+                write!(f, "line {}: mask rows differ in length", line_number)
+            }
+            PatternError::EmptyMask => write!(f, "mask marks no cells"),
+        }
+    }
+}
+
+impl error::Error for PatternError {}
+
+// A board wins when every cell of any one of its pattern's cell groups is
+// marked. Rows and columns are the standard game.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WinPattern {
+    Rows,
+    Columns,
+    Diagonals,
+    FourCorners,
+    X,
+    Blackout,
+    Mask { size: BoardSize, cells: Vec<Cell> },
+}
+
+impl WinPattern {
+    pub fn standard() -> Vec<WinPattern> {
+        vec![WinPattern::Rows, WinPattern::Columns]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            WinPattern::Rows => "rows",
+            WinPattern::Columns => "columns",
+            WinPattern::Diagonals => "diagonals",
+            WinPattern::FourCorners => "corners",
+            WinPattern::X => "x",
+            WinPattern::Blackout => "blackout",
+            WinPattern::Mask { .. } => "mask",
+        }
+    }
+
+    // Parses a grid with one line per row: `X`, `x`, `#` or `1` for a cell
+    // that must be marked, `.`, `-`, `_` or `0` for one that need not be.
+    // Spaces between cells and blank lines are ignored.
+    pub fn parse_mask(text: &str) -> Result<Self, PatternError> {
+        let mut rows: Vec<Vec<bool>> = Vec::new();
+        for (index, line) in text.lines().enumerate() {
+            let mut row = Vec::new();
+            for character in line.chars().filter(|c| !c.is_whitespace()) {
+                row.push(match character {
+                    'X' | 'x' | '#' | '1' => true,
+                    '.' | '-' | '_' | '0' => false,
+                    _ => {
+                        return Err(PatternError::InvalidMaskCharacter {
+                            line_number: index + 1,
+                            character,
+                        })
+                    }
+                });
+            }
+            if row.is_empty() {
+                continue;
+            }
+            if rows.first().is_some_and(|first| first.len() != row.len()) {
+                return Err(PatternError::RaggedMask {
+                    line_number: index + 1,
+                });
+            }
+            rows.push(row);
+        }
+
+        let cells: Vec<Cell> = rows
+            .iter()
+            .enumerate()
+            .flat_map(|(row, marks)| {
+                marks
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, &marked)| marked)
+                    .map(move |(column, _)| (row, column))
+            })
+            .collect();
+        if cells.is_empty() {
+            return Err(PatternError::EmptyMask);
+        }
+        let size = BoardSize {
+            rows: rows.len(),
+            columns: rows[0].len(),
+        };
+        Ok(WinPattern::Mask { size, cells })
+    }
+
+    pub fn cell_groups(&self, size: BoardSize) -> Result<Vec<Vec<Cell>>, PatternError> {
+        let BoardSize { rows, columns } = size;
+        let last_row = rows.saturating_sub(1);
+        let last_column = columns.saturating_sub(1);
+        let square = || {
+            if rows == columns {
+                Ok(())
+            } else {
+                Err(PatternError::NotSquare {
+                    pattern: self.name().to_string(),
+                    size,
+                })
+            }
+        };
+        let diagonals = || -> Vec<Vec<Cell>> {
+            vec![
+                (0..rows).map(|i| (i, i)).collect(),
+                (0..rows).map(|i| (i, last_column - i)).collect(),
+            ]
+        };
+
+        Ok(match self {
+            WinPattern::Rows => (0..rows)
+                .map(|row| (0..columns).map(|column| (row, column)).collect())
+                .collect(),
+            WinPattern::Columns => (0..columns)
+                .map(|column| (0..rows).map(|row| (row, column)).collect())
+                .collect(),
+            WinPattern::Diagonals => {
+                square()?;
+                diagonals()
+            }
+            WinPattern::FourCorners => vec![vec![
+                (0, 0),
+                (0, last_column),
+                (last_row, 0),
+                (last_row, last_column),
+            ]],
+            WinPattern::X => {
+                square()?;
+                vec![diagonals().concat()]
+            }
+            WinPattern::Blackout => vec![(0..rows)
+                .flat_map(|row| (0..columns).map(move |column| (row, column)))
+                .collect()],
+            WinPattern::Mask { size: mask, cells } => {
+                if *mask != size {
+                    return Err(PatternError::MaskSizeMismatch {
+                        mask: *mask,
+                        board: size,
+                    });
+                }
+                vec![cells.clone()]
+            }
+        })
+    }
+}
+
+impl FromStr for WinPattern {
+    type Err = PatternError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "rows" => Ok(WinPattern::Rows),
+            "columns" => Ok(WinPattern::Columns),
+            "diagonals" => Ok(WinPattern::Diagonals),
+            "corners" => Ok(WinPattern::FourCorners),
+            "x" => Ok(WinPattern::X),
+            "blackout" => Ok(WinPattern::Blackout),
+            _ => Err(PatternError::UnknownPattern(name.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests_pattern {
+    use super::*;
+
+    const SQUARE: BoardSize = BoardSize {
+        rows: 3,
+        columns: 3,
+    };
+
+    #[test]
+    fn test_built_in_groups() {
+        assert_eq!(
+            WinPattern::Rows.cell_groups(SQUARE).unwrap()[1],
+            vec![(1, 0), (1, 1), (1, 2)]
+        );
+        assert_eq!(
+            WinPattern::Columns.cell_groups(SQUARE).unwrap()[2],
+            vec![(0, 2), (1, 2), (2, 2)]
+        );
+        assert_eq!(
+            WinPattern::Diagonals.cell_groups(SQUARE).unwrap(),
+            vec![vec![(0, 0), (1, 1), (2, 2)], vec![(0, 2), (1, 1), (2, 0)]]
+        );
+        assert_eq!(
+            WinPattern::FourCorners.cell_groups(SQUARE).unwrap(),
+            vec![vec![(0, 0), (0, 2), (2, 0), (2, 2)]]
+        );
+        assert_eq!(WinPattern::X.cell_groups(SQUARE).unwrap()[0].len(), 6);
+        assert_eq!(
+            WinPattern::Blackout.cell_groups(SQUARE).unwrap()[0].len(),
+            9
+        );
+    }
+
+    #[test]
+    fn test_diagonals_need_square_boards() {
+        let size = BoardSize {
+            rows: 3,
+            columns: 4,
+        };
+        assert_eq!(
+            WinPattern::X.cell_groups(size),
+            Err(PatternError::NotSquare {
+                pattern: "x".to_string(),
+                size
+            })
+        );
+        assert!(WinPattern::FourCorners.cell_groups(size).is_ok());
+    }
+
+    #[test]
+    fn test_parse_mask() {
+        let mask = WinPattern::parse_mask("X . X\n. X .\n\nX . X\n").unwrap();
+        assert_eq!(
+            mask.cell_groups(SQUARE).unwrap(),
+            vec![vec![(0, 0), (0, 2), (1, 1), (2, 0), (2, 2)]]
+        );
+        assert_eq!(
+            mask.cell_groups(BoardSize {
+                rows: 5,
+                columns: 5
+            }),
+            Err(PatternError::MaskSizeMismatch {
+                mask: SQUARE,
+                board: BoardSize {
+                    rows: 5,
+                    columns: 5
+                }
+            })
+        );
+
+        assert_eq!(
+            WinPattern::parse_mask("X.\nX?"),
+            Err(PatternError::InvalidMaskCharacter {
+                line_number: 2,
+                character: '?'
+            })
+        );
+        assert_eq!(
+            WinPattern::parse_mask("X.\nX"),
+            Err(PatternError::RaggedMask { line_number: 2 })
+        );
+        assert_eq!(
+            WinPattern::parse_mask("..\n.."),
+            Err(PatternError::EmptyMask)
+        );
+    }
+
+    #[test]
+    fn test_pattern_names() {
+        assert_eq!("Corners".parse(), Ok(WinPattern::FourCorners));
+        assert_eq!(
+            "stripes".parse::<WinPattern>(),
+            Err(PatternError::UnknownPattern("stripes".to_string()))
+        );
+    }
+}
@@ -0,0 +1,39 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BoardWin {
+    pub board: usize,
+    pub draw_index: usize,
+    pub number: usize,
+    pub score: usize,
+}
+
+// Wins in the order they happened; boards that win on the same draw are
+// ordered by their position in the input.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct BingoTimeline {
+    pub wins: Vec<BoardWin>,
+    pub never_won: Vec<usize>,
+}
+
+impl BingoTimeline {
+    pub fn first_winner(&self) -> Option<&BoardWin> {
+        self.wins.first()
+    }
+
+    pub fn last_winner(&self) -> Option<&BoardWin> {
+        self.wins.last()
+    }
+
+    pub fn win_of(&self, board: usize) -> Option<&BoardWin> {
+        self.wins.iter().find(|win| win.board == board)
+    }
+
+    // The score of the first board to win, or 0 if none does.
+    pub fn first_score(&self) -> usize {
+        self.first_winner().map_or(0, |win| win.score)
+    }
+
+    // The score of the last board to win, or 0 if none does.
+    pub fn last_score(&self) -> usize {
+        self.last_winner().map_or(0, |win| win.score)
+    }
+}
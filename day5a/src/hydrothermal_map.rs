@@ -39,8 +39,7 @@ impl HydrothermalMap {
         for line in reader.lines() {
             let line: String = line.unwrap();
             let parse_result: Option<HydrothermalLineSegment> = self.parse_line_segment(line);
-            if let Some(..) = parse_result {
-                let unwrapped_line_segment: HydrothermalLineSegment = parse_result.unwrap();
+            if let Some(unwrapped_line_segment) = parse_result {
                 if unwrapped_line_segment.x1 > max_x_size {
                     max_x_size = unwrapped_line_segment.x1;
                 }
@@ -37,7 +37,7 @@ impl CrabArmy {
     }
 
     pub fn average(&self) -> f32 {
-        return self.crabs.iter().sum::<usize>() as f32 / self.crabs.len() as f32;
+        self.crabs.iter().sum::<usize>() as f32 / self.crabs.len() as f32
     }
 
     pub fn median(&mut self) -> f32 {
@@ -39,44 +39,39 @@ impl LavaTubes {
     }
 
     pub fn is_low_point(&self, row_position: usize, col_position: usize) -> bool {
-        let is_low_point_above: bool;
-        let is_low_point_below: bool;
-        let is_low_point_left: bool;
-        let is_low_point_right: bool;
-
         let this_value: u32 = self.input_values[row_position][col_position];
 
-        if row_position != 0 {
+        let is_low_point_above: bool = if row_position != 0 {
             // Check above
             let compare_to: u32 = self.input_values[row_position - 1][col_position];
-            is_low_point_above = this_value < compare_to;
+            this_value < compare_to
         } else {
-            is_low_point_above = true;
-        }
+            true
+        };
 
-        if row_position + 1 < self.input_values.len() {
+        let is_low_point_below: bool = if row_position + 1 < self.input_values.len() {
             // Check Below
             let compare_to: u32 = self.input_values[row_position + 1][col_position];
-            is_low_point_below = this_value < compare_to;
+            this_value < compare_to
         } else {
-            is_low_point_below = true;
-        }
+            true
+        };
 
-        if col_position != 0 {
+        let is_low_point_left: bool = if col_position != 0 {
             // Check Left
             let compare_to: u32 = self.input_values[row_position][col_position - 1];
-            is_low_point_left = this_value < compare_to;
+            this_value < compare_to
         } else {
-            is_low_point_left = true;
-        }
+            true
+        };
 
-        if col_position + 1 < self.input_values[row_position].len() {
+        let is_low_point_right: bool = if col_position + 1 < self.input_values[row_position].len() {
             // Check Right
             let compare_to: u32 = self.input_values[row_position][col_position + 1];
-            is_low_point_right = this_value < compare_to;
+            this_value < compare_to
         } else {
-            is_low_point_right = true;
-        }
+            true
+        };
 
         is_low_point_above && is_low_point_below && is_low_point_left && is_low_point_right
     }